@@ -1,49 +1,181 @@
-use std::collections::HashMap;
+use alloc::collections::BTreeMap;
+use core::fmt::{self, Write};
 
-use crate::StlModel;
+use crate::{ParseOptions, StlFormat, StlModel};
+use crate::prelude::*;
 use crate::error::{Error, Result};
 use crate::geometry::Triangle;
 
 #[cfg(test)]
 mod tests;
 
+/// A piece of an ASCII STL file, as returned by [`tokenize`].
 #[derive(Debug, Clone, PartialEq)]
-enum Token {
+pub enum Token {
+    /// The rest of the first line after "solid ". This is always the first
+    /// token.
     Header(String),
-    Keyword(String),
+    /// One of the keywords of the format, such as `facet` or `vertex`.
+    Keyword(&'static str),
+    /// A number.
     Float(f32),
+    /// The rest of a line that is not part of the STL format, such as the
+    /// `color` lines that some exporters add inside facets.
+    Unknown(String)
 }
 
 pub fn parse_ascii_stl(bytes: &[u8]) -> Result<StlModel> {
-    let mut tokens = tokenize_ascii_stl(bytes)?.into_iter();
+    parse_ascii_stl_with_options(bytes, &ParseOptions::default())
+}
+
+pub fn parse_ascii_stl_with_options(bytes: &[u8], options: &ParseOptions) -> Result<StlModel> {
+    parse_tokens(tokenize(bytes)?, options)
+}
+
+/// Read an ASCII STL file a line at a time.
+///
+/// This parses the same files as [`parse_stl`](crate::parse_stl) does for
+/// ASCII data, but without needing the whole file in memory first, which
+/// helps with very large files. Only one line and the triangles read so far
+/// are held at once. Errors reading from `reader` are returned as errors of
+/// kind [`ErrorKind::Io`].
+///
+/// Only ASCII files can be read this way; for binary files, read the bytes
+/// and use [`parse_stl`](crate::parse_stl).
+///
+/// This function is only available with the `std` feature.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+/// use pk_stl::read_ascii_stl;
+///
+/// let text = "solid triangle
+/// facet normal 0 0 1
+///     outer loop
+///         vertex 0 0 0
+///         vertex 1 0 0
+///         vertex 0 1 0
+///     endloop
+/// endfacet
+/// endsolid triangle
+/// ";
+///
+/// let model = read_ascii_stl(Cursor::new(text)).unwrap();
+///
+/// assert_eq!(model.header, "triangle");
+/// assert_eq!(model.triangles.len(), 1);
+/// ```
+///
+/// [`ErrorKind::Io`]: crate::error::ErrorKind::Io
+#[cfg(feature = "std")]
+pub fn read_ascii_stl<R: std::io::BufRead>(mut reader: R) -> Result<StlModel> {
+    let mut line = Vec::new();
+    reader.read_until(b'\n', &mut line)?;
+
+    if !line.starts_with(b"solid ") {
+        return Err(Error::ascii("Model must start with 'solid ' keyword"));
+    }
+
+    let header = line[6..].iter()
+        .take_while(|b| !matches!(b, b'\0' | b'\r' | b'\n'))
+        .map(|b| *b as char)
+        .collect();
+
+    let tokens = LineTokens {
+        reader,
+        line,
+        keywords: KeywordRegex::compile(&KEYWORDS),
+        pending: [Ok(Token::Header(header))].into(),
+        finished: false
+    };
+
+    parse_tokens(tokens, &ParseOptions::default())
+}
+
+/// Parse a model from the tokens of an ASCII file, which start with the
+/// header.
+fn parse_tokens(tokens: impl Iterator<Item = Result<Token>>, options: &ParseOptions) -> Result<StlModel> {
+    let mut tokens = UntilError {
+        tokens: tokens.filter_map(|token| match token {
+            Ok(Token::Unknown(text)) if options.reject_unknown_keywords => {
+                Some(Err(Error::ascii(&format!("Unexpected text: {:?}", text))))
+            },
+            Ok(Token::Unknown(_)) => None,
+            token => Some(token)
+        }),
+        error: None
+    };
+
+    let model = parse_model(&mut tokens, options);
+
+    // A token that failed to read ends the tokens early, which would be
+    // reported as finding the end of the file.
+    match tokens.error {
+        Some(error) => Err(error),
+        None => model
+    }
+}
 
+fn parse_model<I>(tokens: &mut I, options: &ParseOptions) -> Result<StlModel>
+    where I: Iterator<Item = Token>
+{
     let header = match tokens.next() {
         Some(Token::Header(header)) => header,
         _ => return Err(Error::ascii("Invalid header"))
     };
 
     let mut triangles = Vec::new();
+    let mut found_endsolid = false;
 
     while let Some(t) = tokens.next() {
-        if t != Token::Keyword("facet".to_string()) {
-            if t == Token::Keyword("endsolid".to_string()) {
+        if t != Token::Keyword("facet") {
+            if t == Token::Keyword("endsolid") {
+                found_endsolid = true;
                 break;
             } else {
-                return Err(Error::ascii("Expected facet or endsolid"));
+                return Err(unexpected("'facet' or 'endsolid'", Some(t)));
             }
         }
 
-        let normal = parse_normal(&mut tokens)?;
-        let vertices = parse_vertices(&mut tokens)?;
+        let normal = parse_normal(tokens)?;
+        let vertices = parse_vertices(tokens)?;
 
-        if tokens.next() != Some(Token::Keyword("endfacet".to_string())) {
-            return Err(Error::ascii("Expected endfacet keyword"));
-        }
+        expect_keyword(tokens, "endfacet")?;
+
+        triangles.push(Triangle::from([vertices[0], vertices[1], vertices[2], normal]));
+    }
 
-        triangles.push(Triangle::from([normal, vertices[0], vertices[1], vertices[2]]));
+    if options.require_endsolid && !found_endsolid {
+        return Err(Error::ascii("Expected endsolid keyword at the end of the file"));
     }
 
-    Ok(StlModel { header, triangles })
+    let header = if options.skip_header { String::new() } else { header };
+    let mut model = StlModel::new(header, triangles);
+    model.source_format = Some(StlFormat::Ascii);
+
+    Ok(model)
+}
+
+/// Takes tokens until the first error, which is kept to be returned later.
+struct UntilError<I> {
+    tokens: I,
+    error: Option<Error>
+}
+
+impl<I: Iterator<Item = Result<Token>>> Iterator for UntilError<I> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        match self.tokens.next()? {
+            Ok(token) => Some(token),
+            Err(error) => {
+                self.error = Some(error);
+                None
+            }
+        }
+    }
 }
 
 fn parse_normal<I>(tokens: &mut I) -> Result<[f32; 3]>
@@ -51,15 +183,10 @@ fn parse_normal<I>(tokens: &mut I) -> Result<[f32; 3]>
 {
     let mut normal = [0.0; 3];
 
-    if tokens.next() != Some(Token::Keyword("normal".to_string())) {
-        return Err(Error::ascii("Expected normal keyword"));
-    }
+    expect_keyword(tokens, "normal")?;
 
-    for i in 0..3 {
-        normal[i] = match tokens.next() {
-            Some(Token::Float(f)) => f,
-            _ => return Err(Error::ascii("Expected normal float"))
-        }
+    for value in &mut normal {
+        *value = expect_float(tokens, "a normal coordinate")?;
     }
 
     Ok(normal)
@@ -70,189 +197,401 @@ fn parse_vertices<I>(tokens: &mut I) -> Result<[[f32; 3]; 3]>
 {
     let mut vertices = [[0.0; 3]; 3];
 
-    if tokens.next() != Some(Token::Keyword("outer".to_string())) {
-        return Err(Error::ascii("Expected outer keyword"));
-    }
-
-    if tokens.next() != Some(Token::Keyword("loop".to_string())) {
-        return Err(Error::ascii("Expected loop keyword"));
-    }
+    expect_keyword(tokens, "outer")?;
+    expect_keyword(tokens, "loop")?;
 
-    for i in 0..3 {
-        if tokens.next() != Some(Token::Keyword("vertex".to_string())) {
-            return Err(Error::ascii("Expected vertex keyword"));
-        }
+    for vertex in &mut vertices {
+        expect_keyword(tokens, "vertex")?;
 
-        for j in 0..3 {
-            vertices[i][j] = match tokens.next() {
-                Some(Token::Float(f)) => f,
-                _ => return Err(Error::ascii("Expected vertex float"))
-            }
+        for value in vertex {
+            *value = expect_float(tokens, "a vertex coordinate")?;
         }
     }
 
-    if tokens.next() != Some(Token::Keyword("endloop".to_string())) {
-        return Err(Error::ascii("Expected endloop keyword"));
-    }
+    expect_keyword(tokens, "endloop")?;
 
     Ok(vertices)
 }
 
-fn tokenize_ascii_stl(bytes: &[u8]) -> Result<Vec<Token>> {
-    let mut tokens = Vec::new();
+fn expect_keyword<I>(tokens: &mut I, keyword: &str) -> Result<()>
+    where I: Iterator<Item = Token>
+{
+    match tokens.next() {
+        Some(Token::Keyword(found)) if found == keyword => Ok(()),
+        found => Err(unexpected(&format!("'{}'", keyword), found))
+    }
+}
+
+fn expect_float<I>(tokens: &mut I, expected: &str) -> Result<f32>
+    where I: Iterator<Item = Token>
+{
+    match tokens.next() {
+        Some(Token::Float(value)) => Ok(value),
+        found => Err(unexpected(expected, found))
+    }
+}
+
+/// An error for finding something other than what was expected.
+fn unexpected(expected: &str, found: Option<Token>) -> Error {
+    let found = match found {
+        Some(Token::Header(header)) => format!("the header {:?}", header),
+        Some(Token::Keyword(keyword)) => format!("'{}'", keyword),
+        Some(Token::Float(value)) => format!("the number {}", value),
+        Some(Token::Unknown(text)) => format!("{:?}", text),
+        None => "the end of the file".to_string()
+    };
 
-    let mut data = bytes.into_iter();
+    Error::ascii(&format!("Expected {} but found {}", expected, found))
+}
 
-    let solid_keyword = data.by_ref().take(6).map(|val| { *val }).collect::<Vec<u8>>();
-    if solid_keyword != b"solid " {
+/// Split an ASCII STL file into tokens.
+///
+/// This gives access to the pieces of an ASCII file for reading files with
+/// nonstandard extensions that the parser does not understand. The first
+/// token is always the [header](Token::Header), followed by the keywords and
+/// numbers of the file in order. Tokens stop after the `endsolid` keyword.
+///
+/// Returns an error if the data does not start with "solid ". Errors in the
+/// rest of the file, such as malformed numbers, are returned by the iterator,
+/// which stops after the first error.
+///
+/// # Examples
+///
+/// ```
+/// use pk_stl::{tokenize, Token};
+///
+/// let content = b"solid cube\nfacet normal 0 0 1\ncolor 1 0 0\n";
+/// let tokens: Vec<Token> = tokenize(content)
+///     .unwrap()
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+///
+/// assert_eq!(tokens, vec![
+///     Token::Header("cube".to_string()),
+///     Token::Keyword("facet"),
+///     Token::Keyword("normal"),
+///     Token::Float(0.0),
+///     Token::Float(0.0),
+///     Token::Float(1.0),
+///     Token::Unknown("color 1 0 0".to_string())
+/// ]);
+/// ```
+pub fn tokenize(bytes: &[u8]) -> Result<impl Iterator<Item = Result<Token>> + '_> {
+    if !bytes.starts_with(b"solid ") {
         return Err(Error::ascii("Model must start with 'solid ' keyword"));
     }
 
-    let mut data = data.map(|val| { *val as char }).peekable();
+    Ok(Tokens {
+        bytes,
+        position: 6,
+        keywords: KeywordRegex::compile(&KEYWORDS),
+        started: false,
+        finished: false
+    })
+}
 
-    let mut header = String::new();
+/// The keywords of the ASCII STL format.
+static KEYWORDS: [&str; 8] = [
+    "facet",
+    "outer",
+    "loop",
+    "vertex",
+    "normal",
+    "endloop",
+    "endfacet",
+    "endsolid"
+];
+
+/// The iterator returned by [`tokenize`].
+#[derive(Debug, Clone)]
+struct Tokens<'a> {
+    bytes: &'a [u8],
+    /// The index of the next byte to read.
+    position: usize,
+    keywords: KeywordRegex,
+    /// Whether the header has been returned.
+    started: bool,
+    /// Whether the end of the tokens has been reached.
+    finished: bool
+}
 
-    while let Some(c) = data.next() {
-        match c {
-            '\0' | '\r' | '\n' => break,
-            c => header.push(c)
-        }
+impl<'a> Tokens<'a> {
+    /// Read bytes from the current position while they match.
+    fn skip_while(&mut self, matches: impl Fn(char) -> bool) -> &'a [u8] {
+        let rest = &self.bytes[self.position..];
+        let len = rest.iter().take_while(|b| matches(**b as char)).count();
+        self.position += len;
+
+        &rest[..len]
+    }
+
+    /// Read characters from the current position while they match.
+    fn take_while(&mut self, matches: impl Fn(char) -> bool) -> String {
+        self.skip_while(matches).iter().map(|b| *b as char).collect()
     }
 
-    tokens.push(Token::Header(header));
-
-    // Now parse the rest of the tokens dynamically
-    let keyword_regex = KeywordRegex::compile(&[
-        "facet",
-        "outer",
-        "loop",
-        "vertex",
-        "normal",
-        "endloop",
-        "endfacet",
-        "endsolid"
-    ]);
-
-    loop {
-        println!("Starting loop, next char is {:?}", data.peek());
+    fn next_token(&mut self) -> Option<Result<Token>> {
+        if !self.started {
+            self.started = true;
+
+            let header = self.take_while(|c| !matches!(c, '\0' | '\r' | '\n'));
+            // Skip the line ending
+            self.position = (self.position + 1).min(self.bytes.len());
+
+            return Some(Ok(Token::Header(header)));
+        }
+
         // Skip whitespace
-        if let Some(c) = data.peek() {
-            if c.is_whitespace() {
-                println!("Skipping whitespace");
-                data.next();
-                continue;
-            }
+        self.skip_while(char::is_whitespace);
+
+        let c = *self.bytes.get(self.position)? as char;
+
+        // Look for numbers in sign-mantissa-e-sign-exponent format. Either
+        // side of the decimal point may be left out, as in `1.`, `.5`, and
+        // `1.e3`, which some exporters write.
+        if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' {
+            let number = self.skip_while(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'));
+
+            // The bytes are all ASCII, so they are always valid UTF-8.
+            let number = core::str::from_utf8(number).unwrap_or_default();
+
+            return Some(match number.parse::<f32>() {
+                Ok(value) => Ok(Token::Float(value)),
+                Err(_) => Err(Error::ascii(&format!("Invalid float '{}'", number)))
+            });
         }
 
-        // Look for numbers in sign-mantissa-e-sign-exponent format
-        if let Some(c) = data.peek() {
-            if c.is_ascii_digit() || *c == '-' || *c == '+' || *c == '.' {
-                let mut number = String::new();
-
-                while let Some(c) = data.peek() {
-                    if c.is_ascii_digit() || *c == '-' || *c == '+' || *c == '.' || *c == 'e' || *c == 'E' {
-                        number.push(*c);
-                        data.next();
-                    } else {
-                        break;
-                    }
-                }
+        // Look for keywords
+        let mut chars = self.bytes[self.position..].iter().map(|b| *b as char);
+        if let Some(keyword) = self.keywords.find(&mut chars) {
+            self.position += keyword.len();
 
-                tokens.push(Token::Float(number.parse::<f32>().map_err(|_| { Error::ascii("Invalid float") })?));
-                continue;
+            if keyword == "endsolid" {
+                self.finished = true;
             }
+
+            return Some(Ok(Token::Keyword(keyword)));
         }
 
-        // Look for keywords
-        if let Some(keyword) = keyword_regex.find(&mut data) {
-            let endsolid = keyword == "endsolid";
+        // Anything else is the rest of a line that isn't part of the format
+        let line = self.take_while(|c| c != '\n');
 
-            tokens.push(Token::Keyword(keyword));
+        Some(Ok(Token::Unknown(line.trim_end().to_string())))
+    }
+}
 
-            if endsolid {
-                break;
-            }
-            continue;
+impl Iterator for Tokens<'_> {
+    type Item = Result<Token>;
+
+    fn next(&mut self) -> Option<Result<Token>> {
+        if self.finished {
+            return None;
         }
 
-        // If we get here, we've reached the end of the file
-        if data.peek().is_none() {
-            break;
-        } else {
-            return Err(Error::ascii(format!("Unexpected character: {:?}", data.next()).as_str()));
+        let token = self.next_token();
+
+        if !matches!(token, Some(Ok(_))) {
+            self.finished = true;
         }
+
+        token
     }
+}
 
-    Ok(tokens)
+/// The tokens of an ASCII file read a line at a time, as used by
+/// [`read_ascii_stl`].
+#[cfg(feature = "std")]
+struct LineTokens<R> {
+    reader: R,
+    /// The line being read, kept to reuse its memory.
+    line: Vec<u8>,
+    keywords: KeywordRegex,
+    /// The tokens of the current line that have not been returned yet.
+    pending: alloc::collections::VecDeque<Result<Token>>,
+    /// Whether the end of the tokens has been reached.
+    finished: bool
 }
 
-#[derive(Debug, Clone)]
-struct KeywordRegex {
-    root: KwNode
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead> Iterator for LineTokens<R> {
+    type Item = Result<Token>;
+
+    fn next(&mut self) -> Option<Result<Token>> {
+        loop {
+            if let Some(token) = self.pending.pop_front() {
+                return Some(token);
+            }
+
+            if self.finished {
+                return None;
+            }
+
+            self.line.clear();
+            match self.reader.read_until(b'\n', &mut self.line) {
+                Ok(0) => return None,
+                Ok(_) => {},
+                Err(error) => {
+                    self.finished = true;
+                    return Some(Err(error.into()));
+                }
+            }
+
+            // Tokens never span lines, so each line can be split on its own.
+            // The keywords are moved in and back out to avoid compiling them
+            // again for every line.
+            let mut tokens = Tokens {
+                bytes: &self.line,
+                position: 0,
+                keywords: core::mem::take(&mut self.keywords),
+                started: true,
+                finished: false
+            };
+            self.pending.extend(&mut tokens);
+            self.keywords = tokens.keywords;
+
+            self.finished = matches!(self.pending.back(), Some(Err(_) | Ok(Token::Keyword("endsolid"))));
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
-enum KwNode {
-    Branch(HashMap<char, KwNode>),
+/// Write an ASCII STL model with the given header line.
+pub(crate) fn write_ascii_stl<W: Write>(out: &mut W, header: &str, triangles: &[Triangle]) -> fmt::Result {
+    writeln!(out, "solid {}", header)?;
+
+    for triangle in triangles {
+        let n = triangle.normal;
+        writeln!(out, "facet normal {} {} {}", AsciiFloat(n.x), AsciiFloat(n.y), AsciiFloat(n.z))?;
+        writeln!(out, "    outer loop")?;
+        for v in &triangle.vertices {
+            writeln!(out, "        vertex {} {} {}", AsciiFloat(v.x), AsciiFloat(v.y), AsciiFloat(v.z))?;
+        }
+        writeln!(out, "    endloop")?;
+        writeln!(out, "endfacet")?;
+    }
+
+    Ok(())
 }
 
-impl KeywordRegex {
-    pub fn find<I>(&self, chars: &mut I) -> Option<String>
-        where I: Iterator<Item = char>
-    {
-        self.root.find(chars)
+/// Formats a float for ASCII STL output.
+///
+/// This is the same as the `{:e}` format, which already produces the shortest
+/// text that parses back to the same value, except that the mantissa always
+/// has at least one fractional digit (`1.0e0` rather than `1e0`). Some CAD
+/// programs reject numbers without a decimal point.
+///
+/// Because the shortest exact text is used rather than a fixed precision,
+/// every finite `f32` is read back bit for bit, including negative zero and
+/// subnormal numbers. No `f32` needs more than 9 significant digits.
+///
+/// The number is formatted into a buffer on the stack, so no allocation is
+/// made for each number written.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AsciiFloat(pub f32);
+
+impl fmt::Display for AsciiFloat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut buffer = StackBuffer::default();
+        write!(buffer, "{:e}", self.0)?;
+        let text = buffer.as_str();
+
+        match text.find('e') {
+            Some(e) if !text[..e].contains('.') => {
+                write!(f, "{}.0{}", &text[..e], &text[e..])
+            },
+            _ => f.write_str(text)
+        }
     }
+}
 
-    pub fn compile(keywords: &[&str]) -> KeywordRegex {
-        let mut root = KwNode::Branch(HashMap::new());
+/// A small fixed-size buffer that can be formatted into.
+#[derive(Debug, Default)]
+struct StackBuffer {
+    bytes: [u8; 32],
+    len: usize
+}
+
+impl StackBuffer {
+    fn as_str(&self) -> &str {
+        // Only whole `&str`s are ever copied in, so this is always valid.
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap_or_default()
+    }
+}
 
-        for keyword in keywords {
-            root.add(keyword);
+impl Write for StackBuffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        if end > self.bytes.len() {
+            return Err(fmt::Error);
         }
 
-        KeywordRegex { root }
+        self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+
+        Ok(())
     }
 }
 
-impl KwNode {
-    pub fn find<I>(&self, chars: &mut I) -> Option<String>
-        where I: Iterator<Item = char>
+/// Matches the start of some text against a set of keywords.
+///
+/// The keywords are stored in a trie, with one node for each prefix of a
+/// keyword.
+#[derive(Debug, Clone, Default)]
+struct KeywordRegex {
+    root: KwNode
+}
+
+#[derive(Debug, Clone, Default)]
+struct KwNode {
+    /// The keyword that ends at this node, if there is one.
+    keyword: Option<&'static str>,
+    children: BTreeMap<char, KwNode>
+}
+
+impl KeywordRegex {
+    /// Find the longest keyword at the start of `chars`.
+    ///
+    /// Only the characters of the keyword are taken from the iterator, so the
+    /// next character is the one straight after it. If no keyword matches,
+    /// nothing is taken. The keyword is returned from the list it was compiled
+    /// from, so nothing is allocated.
+    pub fn find<I>(&self, chars: &mut I) -> Option<&'static str>
+        where I: Iterator<Item = char> + Clone
     {
-        match self {
-            KwNode::Branch(map) if map.is_empty() => Some(String::new()),
-            KwNode::Branch(map) => {
-                if let Some(c) = chars.next() {
-                    if let Some(node) = map.get(&c) {
-                        node.find(chars).and_then(|mut s| {
-                            s.insert(0, c);
-                            Some(s)
-                        })
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
+        let mut node = &self.root;
+        let mut longest = None;
+
+        // Walk a copy of the iterator as far down the trie as it goes,
+        // remembering the last keyword passed along the way.
+        for (i, c) in chars.clone().enumerate() {
+            match node.children.get(&c) {
+                Some(child) => node = child,
+                None => break
+            }
+
+            if let Some(keyword) = node.keyword {
+                longest = Some((i + 1, keyword));
             }
         }
+
+        let (len, keyword) = longest?;
+        chars.take(len).for_each(drop);
+
+        Some(keyword)
     }
 
-    pub fn add(&mut self, keyword: &str) {
-        let mut chars = keyword.chars();
+    pub fn compile(keywords: &[&'static str]) -> KeywordRegex {
+        let mut root = KwNode::default();
 
-        if let Some(c) = chars.next() {
-            match self {
-                KwNode::Branch(map) => {
-                    let node = map.entry(c).or_insert_with(|| {
-                        KwNode::Branch(HashMap::new())
-                    });
+        for &keyword in keywords {
+            let mut node = &mut root;
 
-                    node.add(&chars.collect::<String>());
-                }
+            for c in keyword.chars() {
+                node = node.children.entry(c).or_default();
             }
+
+            node.keyword = Some(keyword);
         }
 
-        // If the string is empty we can do nothing because an empty branch
-        // is treated as a leaf node.
+        KeywordRegex { root }
     }
-}
\ No newline at end of file
+}
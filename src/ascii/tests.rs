@@ -7,7 +7,7 @@ fn test_single_keyword_regex() {
 
     assert_eq!(
         regex.find(&mut "foo".chars()),
-        Some("foo".to_string())
+        Some("foo")
     );
 }
 
@@ -18,15 +18,15 @@ fn test_multiple_keyword_regex() {
 
     assert_eq!(
         regex.find(&mut "foo".chars()),
-        Some("foo".to_string())
+        Some("foo")
     );
     assert_eq!(
         regex.find(&mut "bar".chars()),
-        Some("bar".to_string())
+        Some("bar")
     );
     assert_eq!(
         regex.find(&mut "baz".chars()),
-        Some("baz".to_string())
+        Some("baz")
     );
 }
 
@@ -38,20 +38,77 @@ fn test_keyword_regex_only_takes_what_it_needs() {
     let mut foobar_iterator = "foobar".chars();
     assert_eq!(
         regex.find(&mut foobar_iterator),
-        Some("foo".to_string())
+        Some("foo")
     );
     assert_eq!(foobar_iterator.next(), Some('b'));
 
     assert_eq!(
         regex.find(&mut "barbaz".chars()),
-        Some("bar".to_string())
+        Some("bar")
     );
     assert_eq!(
         regex.find(&mut "bazfoo".chars()),
-        Some("baz".to_string())
+        Some("baz")
     );
 }
 
+#[test]
+fn test_keyword_regex_takes_nothing_without_a_match() {
+    let regex = KeywordRegex::compile(&["endloop", "endfacet", "endsolid"]);
+
+    // Truncated keywords don't match, and leave the input where it was.
+    let mut truncated = "endsol".chars();
+    assert_eq!(regex.find(&mut truncated), None);
+    assert_eq!(truncated.as_str(), "endsol");
+
+    let mut other = "color 1 0 0".chars();
+    assert_eq!(regex.find(&mut other), None);
+    assert_eq!(other.as_str(), "color 1 0 0");
+
+    assert_eq!(regex.find(&mut "".chars()), None);
+}
+
+#[test]
+fn test_keyword_regex_matches_longest_prefix_keyword() {
+    let regex = KeywordRegex::compile(&["end", "endsolid", "endloop"]);
+
+    let mut endsolid = "endsolid foo".chars();
+    assert_eq!(regex.find(&mut endsolid), Some("endsolid"));
+    assert_eq!(endsolid.as_str(), " foo");
+
+    // Falls back to the shorter keyword when the longer one doesn't finish.
+    let mut truncated = "endsol".chars();
+    assert_eq!(regex.find(&mut truncated), Some("end"));
+    assert_eq!(truncated.as_str(), "sol");
+
+    let mut end = "end".chars();
+    assert_eq!(regex.find(&mut end), Some("end"));
+    assert_eq!(end.next(), None);
+
+    let mut endfacet = "endfacet".chars();
+    assert_eq!(regex.find(&mut endfacet), Some("end"));
+    assert_eq!(endfacet.as_str(), "facet");
+}
+
+#[test]
+fn test_keywords_are_not_allocated() {
+    let cube = crate::parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let ascii = cube.as_ascii() + "endsolid OpenSCAD Model\n";
+
+    let mut count = 0;
+    for token in tokenize(ascii.as_bytes()).unwrap() {
+        if let Token::Keyword(keyword) = token.unwrap() {
+            // Each keyword is the same string as in the list of keywords,
+            // not a copy of it.
+            assert!(KEYWORDS.iter().any(|known| core::ptr::eq(*known, keyword)));
+            count += 1;
+        }
+    }
+
+    // Nine keywords for each triangle, plus `endsolid`.
+    assert_eq!(count, 12 * 9 + 1);
+}
+
 #[test]
 fn test_tokenize() {
     let src = b"solid foo
@@ -64,32 +121,32 @@ facet normal 0.0 0.0 1.0
 endfacet
 endsolid foo";
 
-    let tokens = tokenize_ascii_stl(src).unwrap();
+    let tokens = tokenize(src).unwrap().collect::<Result<Vec<Token>>>().unwrap();
 
     let expected_tokens = vec![
         Token::Header("foo".to_string()),
-        Token::Keyword("facet".to_string()),
-        Token::Keyword("normal".to_string()),
+        Token::Keyword("facet"),
+        Token::Keyword("normal"),
         Token::Float(0.0),
         Token::Float(0.0),
         Token::Float(1.0),
-        Token::Keyword("outer".to_string()),
-        Token::Keyword("loop".to_string()),
-        Token::Keyword("vertex".to_string()),
+        Token::Keyword("outer"),
+        Token::Keyword("loop"),
+        Token::Keyword("vertex"),
         Token::Float(0.0),
         Token::Float(0.0),
         Token::Float(5.0),
-        Token::Keyword("vertex".to_string()),
+        Token::Keyword("vertex"),
         Token::Float(1.0),
         Token::Float(0.0),
         Token::Float(5.0),
-        Token::Keyword("vertex".to_string()),
+        Token::Keyword("vertex"),
         Token::Float(0.0),
         Token::Float(1.0),
         Token::Float(5.0),
-        Token::Keyword("endloop".to_string()),
-        Token::Keyword("endfacet".to_string()),
-        Token::Keyword("endsolid".to_string())
+        Token::Keyword("endloop"),
+        Token::Keyword("endfacet"),
+        Token::Keyword("endsolid")
     ];
 
     assert_eq!(tokens, expected_tokens);
@@ -109,19 +166,257 @@ endsolid foo";
 
     let stl = parse_ascii_stl(src).unwrap();
 
-    let expected_stl = StlModel {
-        header: "foo".to_string(),
-        triangles: vec![
-            Triangle::from(
-                [
-                    [0.0, 0.0, 1.0],
-                    [0.0, 0.0, 5.0],
-                    [1.0, 0.0, 5.0],
-                    [0.0, 1.0, 5.0],
-                ]
-            )
-        ],
-    };
+    let expected_stl = StlModel::new("foo".to_string(), vec![
+        Triangle::from(
+            [
+                [0.0, 0.0, 5.0],
+                [1.0, 0.0, 5.0],
+                [0.0, 1.0, 5.0],
+                [0.0, 0.0, 1.0],
+            ]
+        )
+    ]);
 
     assert_eq!(stl, expected_stl);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_ascii_float_always_has_decimal_point() {
+    assert_eq!(AsciiFloat(1.0).to_string(), "1.0e0");
+    assert_eq!(AsciiFloat(10.0).to_string(), "1.0e1");
+    assert_eq!(AsciiFloat(0.0).to_string(), "0.0e0");
+    assert_eq!(AsciiFloat(-200.0).to_string(), "-2.0e2");
+    assert_eq!(AsciiFloat(1.5).to_string(), "1.5e0");
+    assert_eq!(AsciiFloat(-0.0025).to_string(), "-2.5e-3");
+}
+
+#[test]
+fn test_as_ascii_writes_integers_with_decimal_point() {
+    let model = StlModel::new("foo".to_string(), vec![
+        Triangle::from([
+            [0.0, 0.0, 5.0],
+            [1.0, 0.0, 5.0],
+            [0.0, 1.0, 5.0],
+            [0.0, 0.0, 1.0]
+        ])
+    ]);
+
+    let ascii = model.as_ascii();
+
+    assert!(ascii.contains("facet normal 0.0e0 0.0e0 1.0e0"));
+    assert!(ascii.contains("vertex 1.0e0 0.0e0 5.0e0"));
+    assert_eq!(parse_ascii_stl(ascii.as_bytes()).unwrap(), model);
+}
+
+#[test]
+fn test_ascii_float_round_trips_exactly() {
+    // A simple xorshift generator, so the test is repeatable without needing
+    // a random number crate.
+    let mut state: u32 = 0x9E37_79B9;
+    let mut next_float = || loop {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+
+        let value = f32::from_bits(state);
+        if value.is_finite() {
+            return value;
+        }
+    };
+
+    let special = [
+        0.0,
+        -0.0,
+        f32::MIN_POSITIVE,
+        -f32::MIN_POSITIVE,
+        f32::from_bits(1),
+        -f32::from_bits(1),
+        f32::from_bits(0x007F_FFFF),
+        f32::MAX,
+        f32::MIN,
+        f32::EPSILON,
+        0.1
+    ];
+
+    let mut values: Vec<f32> = special.to_vec();
+    values.extend((0..3000).map(|_| next_float()));
+
+    let triangles: Vec<Triangle> = values.chunks_exact(12)
+        .map(|chunk| Triangle::from([
+            [chunk[0], chunk[1], chunk[2]],
+            [chunk[3], chunk[4], chunk[5]],
+            [chunk[6], chunk[7], chunk[8]],
+            [chunk[9], chunk[10], chunk[11]]
+        ]))
+        .collect();
+    let model = StlModel::new("round trip", triangles);
+
+    let parsed = parse_ascii_stl(model.as_ascii().as_bytes()).unwrap();
+
+    assert_eq!(parsed.triangles.len(), model.triangles.len());
+    for (original, parsed) in model.triangles.iter().zip(&parsed.triangles) {
+        let original_floats = [original.normal, original.vertices[0], original.vertices[1], original.vertices[2]];
+        let parsed_floats = [parsed.normal, parsed.vertices[0], parsed.vertices[1], parsed.vertices[2]];
+
+        for (a, b) in original_floats.iter().zip(&parsed_floats) {
+            for (a, b) in a.as_arr().iter().zip(&b.as_arr()) {
+                assert_eq!(a.to_bits(), b.to_bits(), "{:e} was read back as {:e}", a, b);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_parse_skips_color_lines() {
+    let src = b"solid foo
+facet normal 0.0 0.0 1.0
+    color 0.8 0.2 0.2
+    outer loop
+        vertex 0.0 0.0 5.0
+        vertex 1.0 0.0 5.0
+        vertex 0.0 1.0 5.0
+    endloop
+    attribute 12
+endfacet
+endsolid foo";
+
+    let stl = parse_ascii_stl(src).unwrap();
+
+    assert_eq!(stl, StlModel::new("foo", vec![
+        Triangle::from([
+            [0.0, 0.0, 5.0],
+            [1.0, 0.0, 5.0],
+            [0.0, 1.0, 5.0],
+            [0.0, 0.0, 1.0]
+        ])
+    ]));
+
+    assert!(parse_ascii_stl_with_options(src, &ParseOptions::strict()).is_err());
+}
+
+#[test]
+fn test_tokenize_cube() {
+    let model = crate::parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let ascii = model.as_ascii() + "endsolid OpenSCAD Model\n";
+
+    let tokens = tokenize(ascii.as_bytes()).unwrap().collect::<Result<Vec<Token>>>().unwrap();
+
+    // The header, 21 tokens for each facet, and endsolid.
+    assert_eq!(tokens.len(), 1 + 12 * 21 + 1);
+    assert_eq!(tokens[0], Token::Header("OpenSCAD Model".to_string()));
+    assert_eq!(tokens.last(), Some(&Token::Keyword("endsolid")));
+
+    let floats: Vec<f32> = tokens.iter()
+        .filter_map(|token| match token {
+            Token::Float(value) => Some(*value),
+            _ => None
+        })
+        .collect();
+    let expected: Vec<f32> = model.triangles.iter()
+        .flat_map(|triangle| [triangle.normal, triangle.vertices[0], triangle.vertices[1], triangle.vertices[2]])
+        .flat_map(|v| v.as_arr())
+        .collect();
+
+    assert_eq!(floats, expected);
+}
+
+#[test]
+fn test_tokenize_errors() {
+    assert!(tokenize(b"facet normal 0 0 1").is_err());
+
+    let mut tokens = tokenize(b"solid foo\nfacet normal 1.0.0 0 1").unwrap();
+
+    assert_eq!(tokens.next().unwrap().unwrap(), Token::Header("foo".to_string()));
+    assert_eq!(tokens.next().unwrap().unwrap(), Token::Keyword("facet"));
+    assert_eq!(tokens.next().unwrap().unwrap(), Token::Keyword("normal"));
+    assert!(tokens.next().unwrap().is_err());
+    assert!(tokens.next().is_none());
+}
+
+#[test]
+fn test_tokenize_floats_without_digits_on_one_side() {
+    let tokens = tokenize(b"solid foo\nvertex 1. .5 1.e3 -.25 +2.\n").unwrap()
+        .collect::<Result<Vec<Token>>>()
+        .unwrap();
+
+    assert_eq!(tokens[2..], [
+        Token::Float(1.0),
+        Token::Float(0.5),
+        Token::Float(1000.0),
+        Token::Float(-0.25),
+        Token::Float(2.0)
+    ]);
+}
+
+#[test]
+fn test_invalid_floats_are_named() {
+    for number in ["1.2.3", ".", "1e", "--1"] {
+        let content = format!("solid foo\nfacet normal 0 0 {}\n", number);
+        let error = parse_ascii_stl(content.as_bytes()).unwrap_err();
+
+        assert!(error.to_string().contains(&format!("Invalid float '{}'", number)), "{}", error);
+    }
+}
+
+#[test]
+fn test_errors_name_the_unexpected_token() {
+    let error = |content: &str| parse_ascii_stl(content.as_bytes()).unwrap_err().to_string();
+
+    let wrong_keyword = "solid foo
+facet normal 0 0 1
+    outer loop
+        normal 0 0 0
+";
+    assert!(error(wrong_keyword).contains("Expected 'vertex' but found 'normal'"), "{}", error(wrong_keyword));
+
+    let missing_float = "solid foo
+facet normal 0 0 1
+    outer loop
+        vertex 0 0
+    endloop
+";
+    assert!(error(missing_float).contains("Expected a vertex coordinate but found 'endloop'"));
+
+    let truncated = "solid foo
+facet normal 0 0 1
+    outer loop
+";
+    assert!(error(truncated).contains("Expected 'vertex' but found the end of the file"));
+
+    let number = "solid foo\n1.5\n";
+    assert!(error(number).contains("Expected 'facet' or 'endsolid' but found the number 1.5"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_read_ascii_stl_matches_slice_parser() {
+    use std::io::Cursor;
+
+    let cube = crate::parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let text = cube.as_ascii();
+
+    let parsed = parse_ascii_stl(text.as_bytes()).unwrap();
+    let read = read_ascii_stl(Cursor::new(&text)).unwrap();
+
+    assert_eq!(read, parsed);
+    assert_eq!(read.source_format(), Some(StlFormat::Ascii));
+
+    // Windows line endings, extra lines, and text after endsolid.
+    let windows = text.replace('\n', "\r\n").replacen("endfacet", "color 1 0 0\r\nendfacet", 1) + "endsolid cube\r\nignored";
+    assert_eq!(read_ascii_stl(Cursor::new(&windows)).unwrap(), parse_ascii_stl(windows.as_bytes()).unwrap());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_read_ascii_stl_errors() {
+    use std::io::Cursor;
+
+    assert!(read_ascii_stl(Cursor::new("")).is_err());
+    assert!(read_ascii_stl(Cursor::new(include_bytes!("../../tests/test_cube.stl"))).is_err());
+
+    let error = read_ascii_stl(Cursor::new("solid x\nfacet normal 0 0 1\nouter loop\nvertex 1.2.3e 0 0\n")).unwrap_err();
+    assert!(error.to_string().contains("Invalid float '1.2.3e'"), "{}", error);
+
+    let error = read_ascii_stl(Cursor::new("solid x\nfacet normal 0 0 1\nendfacet\n")).unwrap_err();
+    assert!(error.to_string().contains("Expected 'outer' but found 'endfacet'"), "{}", error);
+}
@@ -1,70 +1,272 @@
-use std::slice::Iter;
-
 use crate::error::{Error, Result};
-use crate::StlModel;
-use crate::geometry::{Vec3, Triangle};
+use crate::{AttributePolicy, ParseOptions, StlFormat, StlModel};
+use crate::prelude::*;
+use crate::geometry::Triangle;
+use crate::io_util::ByteCursor;
+
+#[cfg(test)]
+mod tests;
+
+/// How many triangles are parsed between calls to the progress callback.
+const PROGRESS_INTERVAL: usize = 1024;
+
+pub fn parse_binary_stl_with_options(bytes: &[u8], options: &ParseOptions) -> Result<StlModel> {
+    let mut model = StlModel::new(String::new(), Vec::new());
+
+    parse_binary_into_with_progress(bytes, &mut model, options, |_, _| {})?;
+
+    Ok(model)
+}
+
+/// Parse a binary STL file, reporting progress as it goes.
+///
+/// `on_progress` is called with the number of triangles parsed so far and the
+/// total number of triangles in the file. It is called before every 1024th
+/// triangle, and always once more when all triangles have been parsed, so the
+/// last call has both values equal. This can be used to drive a progress
+/// bar while loading large files.
+///
+/// # Examples
+///
+/// ```
+/// use pk_stl::parse_binary_stl_with_progress;
+///
+/// let content = include_bytes!("../tests/test_cube.stl");
+/// let mut last = (0, 0);
+///
+/// let model = parse_binary_stl_with_progress(content, |parsed, total| {
+///     last = (parsed, total);
+/// }).unwrap();
+///
+/// assert_eq!(last, (12, 12));
+/// ```
+pub fn parse_binary_stl_with_progress(bytes: &[u8], on_progress: impl FnMut(usize, usize)) -> Result<StlModel> {
+    let mut model = StlModel::new(String::new(), Vec::new());
+
+    parse_binary_into_with_progress(bytes, &mut model, &ParseOptions::default(), on_progress)?;
+
+    Ok(model)
+}
+
+/// The byte order of the numbers in a binary STL file.
+///
+/// STL files are always meant to be little-endian, but some old tools wrote
+/// big-endian files. See [`parse_binary_stl_endian`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Endianness {
+    /// Least significant byte first, as the format requires.
+    #[default]
+    Little,
+    /// Most significant byte first.
+    Big
+}
+
+/// Parse a binary STL file with numbers in the given byte order.
+///
+/// The other parsing functions always read little-endian files, as the
+/// format requires. This can recover files from tools that wrongly wrote
+/// every number big-endian: the triangle count, the normals and vertices,
+/// and the attributes. The header is text, so it is read the same either
+/// way.
+///
+/// Big-endian files are copied and converted before parsing, so they take
+/// twice the memory of the file while being read.
+///
+/// # Examples
+///
+/// ```
+/// use pk_stl::{parse_binary_stl_endian, parse_stl, Endianness};
+///
+/// let content = include_bytes!("../tests/test_cube.stl");
+///
+/// let model = parse_binary_stl_endian(content, Endianness::Little).unwrap();
+/// assert_eq!(model, parse_stl(content).unwrap());
+/// ```
+pub fn parse_binary_stl_endian(bytes: &[u8], endian: Endianness) -> Result<StlModel> {
+    match endian {
+        Endianness::Little => parse_binary_stl_with_options(bytes, &ParseOptions::default()),
+        Endianness::Big => parse_binary_stl_with_options(&swap_byte_order(bytes), &ParseOptions::default())
+    }
+}
+
+/// Reverse the bytes of every number in a binary STL file, converting it
+/// between big-endian and little-endian.
+///
+/// Any incomplete number at the end is left as it is.
+fn swap_byte_order(bytes: &[u8]) -> Vec<u8> {
+    let mut swapped = bytes.to_vec();
+
+    if let Some(count) = swapped.get_mut(80..84) {
+        count.reverse();
+    }
+
+    for triangle in swapped.get_mut(84..).unwrap_or_default().chunks_mut(50) {
+        let floats = triangle.len().min(48);
+        for number in triangle[..floats].chunks_exact_mut(4) {
+            number.reverse();
+        }
+
+        if let Some(attribute) = triangle.get_mut(48..50) {
+            attribute.reverse();
+        }
+    }
+
+    swapped
+}
 
-pub fn parse_binary_stl(bytes: &[u8]) -> Result<StlModel> {
-    let mut data = bytes.into_iter();
+/// Parse a binary STL file into an existing model.
+///
+/// The header, triangles, and source format of `model` are replaced by those
+/// from the file, but the memory already allocated for them is reused. When
+/// parsing many files one after another, reusing the same model avoids
+/// allocating a new list of triangles for each file.
+///
+/// If parsing fails, the model is left with the triangles parsed before the
+/// error.
+///
+/// # Examples
+///
+/// ```
+/// use pk_stl::{parse_binary_into, StlModel};
+///
+/// let content = include_bytes!("../tests/test_cube.stl");
+/// let mut model = StlModel::with_capacity("", 12);
+///
+/// parse_binary_into(content, &mut model).unwrap();
+///
+/// assert_eq!(model.header, "OpenSCAD Model");
+/// assert_eq!(model.triangles.len(), 12);
+/// ```
+pub fn parse_binary_into(bytes: &[u8], model: &mut StlModel) -> Result<()> {
+    parse_binary_into_with_progress(bytes, model, &ParseOptions::default(), |_, _| {})
+}
+
+fn parse_binary_into_with_progress(
+    bytes: &[u8],
+    model: &mut StlModel,
+    options: &ParseOptions,
+    mut on_progress: impl FnMut(usize, usize)
+) -> Result<()> {
+    if options.require_matching_size && !matches_binary_size(bytes) {
+        return Err(size_mismatch(bytes));
+    }
+
+    if !matches_binary_size(bytes) && looks_like_text(bytes) {
+        return Err(Error::binary(
+            "File size does not match the binary layout and the content looks \
+             like text; it may be an ASCII STL that was not detected as such \
+             (ASCII files must start with 'solid ')"
+        ));
+    }
+
+    let mut reader = ByteCursor::new(bytes);
+    let header = reader.read_bytes(bytes.len().min(80))?;
+
+    model.header.clear();
+    if !options.skip_header {
+        // Writers pad the header with either nulls or spaces, so both are
+        // trimmed from the end.
+        model.header.push_str(String::from_utf8_lossy(header).trim_end_matches(|c: char| c == '\0' || c.is_whitespace()));
+    }
+
+    let triangle_count = reader.read_u32()
+        .map_err(|_| Error::binary("Invalid triangle count byte sequence"))?;
+
+    // The count comes from the file, so don't trust it for the allocation.
+    let capacity = (triangle_count as usize).min(reader.remaining() / 50);
+    let triangles = &mut model.triangles;
+    triangles.clear();
+    triangles.reserve(capacity);
+
+    model.source_format = Some(StlFormat::Binary);
+
+    for i in 0..(triangle_count as usize) {
+        if i % PROGRESS_INTERVAL == 0 {
+            on_progress(i, triangle_count as usize);
+        }
 
-    let header: Vec<u8> = data.by_ref().take(80).map(|val| { *val }).collect();
-    let header: String = String::from_utf8_lossy(&header).trim_end_matches("\0").to_string();
+        // Files truncated in the middle of the attribute are tolerated, with
+        // the missing bytes treated as zero.
+        if reader.remaining() < 48 {
+            return Err(Error::binary(&format!(
+                "Unexpected end of data in triangle {} of {}", i + 1, triangle_count
+            )));
+        }
 
-    let triangle_count = {
-        let mut raw = [0; 4];
+        let mut raw = [0u8; 50];
+        let available = reader.read_bytes(reader.remaining().min(50))?;
+        raw[..available.len()].copy_from_slice(available);
 
-        for i in 0..4 {
-            raw[i] = match data.next() {
-                Some(val) => *val,
-                None => return Err(Error::binary("Invalid trianlge count byte sequence"))
+        let mut triangle = Triangle::from_le_bytes(&raw);
+        match options.attributes {
+            AttributePolicy::Ignore => triangle.attribute = 0,
+            AttributePolicy::Preserve => {},
+            AttributePolicy::ValidColorsOnly => {
+                if triangle.color().is_none() {
+                    triangle.attribute = 0;
+                }
             }
         }
 
-        u32::from_le_bytes(raw)
+        triangles.push(triangle);
+    }
+
+    on_progress(triangles.len(), triangle_count as usize);
+
+    Ok(())
+}
+
+/// The error for data whose length doesn't match its triangle count, saying
+/// how far off it is.
+fn size_mismatch(bytes: &[u8]) -> Error {
+    let Some(count) = bytes.get(80..84) else {
+        return Error::binary("File is too short to hold a binary STL header and triangle count");
     };
 
-    let mut triangles: Vec<Triangle> = Vec::with_capacity(triangle_count as usize);
-
-    for _ in 0..(triangle_count as usize) {
-        let normal = read_f32_triplet(&mut data)?;
-        let vert_a = read_f32_triplet(&mut data)?;
-        let vert_b = read_f32_triplet(&mut data)?;
-        let vert_c = read_f32_triplet(&mut data)?;
-
-        // For now we just ignore the attribute byte count
-        // TODO: Possibly support attributes, but not priority.
-        let _ = data.next();
-        let _ = data.next();
-
-        triangles.push(Triangle {
-            normal: Vec3::new(normal),
-            vertices: [
-                Vec3::new(vert_a),
-                Vec3::new(vert_b),
-                Vec3::new(vert_c)
-            ]
-        })
-    }
+    let count = u32::from_le_bytes([count[0], count[1], count[2], count[3]]);
+    let expected = (count as u64) * 50 + 84;
+    let actual = bytes.len() as u64;
 
-    Ok(StlModel { header, triangles })
+    if actual > expected {
+        Error::binary(&format!(
+            "File has {} unexpected bytes after the last of its {} triangles",
+            actual - expected, count
+        ))
+    } else {
+        Error::binary(&format!(
+            "File is {} bytes too short to hold the {} triangles given after the header",
+            expected - actual, count
+        ))
+    }
 }
 
-fn read_f32_triplet<'a>(data: &mut Iter<'a, u8>) -> Result<[f32; 3]> {
-    Ok([
-        read_f32(data)?,
-        read_f32(data)?,
-        read_f32(data)?
-    ])
+/// Check whether the length of the data is exactly what the triangle count
+/// stored after the header says it should be.
+pub(crate) fn matches_binary_size(bytes: &[u8]) -> bool {
+    if bytes.len() < 84 {
+        return false;
+    }
+
+    let count = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]);
+
+    (count as u64) * 50 + 84 == bytes.len() as u64
 }
 
-fn read_f32<'a>(data: &mut Iter<'a, u8>) -> Result<f32> {
-    let mut raw = [0; 4];
-    for item in &mut raw {
-        *item = match data.next() {
-            Some(val) => *val,
-            None => return Err(Error::binary("Invalid trianlge count byte sequence"))
-        };
+/// Check whether the start of the data is mostly printable ASCII text.
+///
+/// Binary headers are usually padded with null bytes and the triangle data
+/// that follows is mostly unprintable, so a high ratio of printable characters
+/// is a strong hint that the data is actually an ASCII file.
+fn looks_like_text(bytes: &[u8]) -> bool {
+    let sample = &bytes[..bytes.len().min(256)];
+
+    if sample.is_empty() {
+        return false;
     }
 
-    Ok(f32::from_le_bytes(raw))
+    let printable = sample.iter()
+        .filter(|b| b.is_ascii_graphic() || b.is_ascii_whitespace())
+        .count();
+
+    printable * 100 >= sample.len() * 95
 }
\ No newline at end of file
@@ -0,0 +1,151 @@
+use super::*;
+
+fn parse_binary_stl(bytes: &[u8]) -> Result<StlModel> {
+    parse_binary_stl_with_options(bytes, &ParseOptions::default())
+}
+
+#[test]
+fn test_misdetected_ascii_gives_helpful_error() {
+    let src = b"SOLID foo
+facet normal 0.0 0.0 1.0
+    outer loop
+        vertex 0.0 0.0 5.0
+        vertex 1.0 0.0 5.0
+        vertex 0.0 1.0 5.0
+    endloop
+endfacet
+endsolid foo";
+
+    let err = parse_binary_stl(src).unwrap_err();
+
+    assert!(err.to_string().contains("ASCII"));
+}
+
+#[test]
+fn test_binary_header_is_not_mistaken_for_text() {
+    let content = include_bytes!("../../tests/test_cube.stl");
+
+    assert!(matches_binary_size(content));
+    assert!(!looks_like_text(content));
+    assert!(parse_binary_stl(content).is_ok());
+}
+
+#[test]
+fn test_header_padding_is_trimmed() {
+    let content = include_bytes!("../../tests/test_cube.stl");
+
+    for padding in [b' ', b'\0'] {
+        let mut padded = content.to_vec();
+        padded[..80].fill(padding);
+        let text = b"My  Model\twith  ";
+        padded[..text.len()].copy_from_slice(text);
+
+        let model = parse_binary_stl(&padded).unwrap();
+        assert_eq!(model.header, "My  Model\twith");
+    }
+
+    // A mix of both, as well as the newline OpenSCAD adds.
+    let mut mixed = content.to_vec();
+    mixed[15..40].fill(b' ');
+    assert_eq!(parse_binary_stl(&mixed).unwrap().header, "OpenSCAD Model");
+}
+
+#[test]
+fn test_truncated_triangle_data_is_an_error() {
+    let content = include_bytes!("../../tests/test_cube.stl");
+
+    // Missing only the attribute of the last triangle is tolerated.
+    let model = parse_binary_stl(&content[..content.len() - 2]).unwrap();
+    assert_eq!(model.triangles.len(), 12);
+
+    let err = parse_binary_stl(&content[..content.len() - 10]).unwrap_err();
+    assert!(err.to_string().contains("triangle 12 of 12"));
+
+    let err = parse_binary_stl(&content[..82]).unwrap_err();
+    assert!(err.to_string().contains("Invalid triangle count"), "{}", err);
+}
+
+#[test]
+fn test_trailing_bytes_are_an_error_when_strict() {
+    let content = include_bytes!("../../tests/test_cube.stl");
+    let strict = ParseOptions::strict();
+
+    let mut trailing = content.to_vec();
+    trailing.extend_from_slice(b"junk");
+
+    assert_eq!(parse_binary_stl(&trailing).unwrap().triangles.len(), 12);
+    let err = parse_binary_stl_with_options(&trailing, &strict).unwrap_err();
+    assert!(err.to_string().contains("4 unexpected bytes after the last of its 12 triangles"), "{}", err);
+
+    // A count one too low leaves a whole triangle over.
+    let mut low_count = content.to_vec();
+    low_count[80..84].copy_from_slice(&11u32.to_le_bytes());
+    assert_eq!(parse_binary_stl(&low_count).unwrap().triangles.len(), 11);
+    let err = parse_binary_stl_with_options(&low_count, &strict).unwrap_err();
+    assert!(err.to_string().contains("50 unexpected bytes after the last of its 11 triangles"), "{}", err);
+
+    let err = parse_binary_stl_with_options(&content[..content.len() - 2], &strict).unwrap_err();
+    assert!(err.to_string().contains("2 bytes too short to hold the 12 triangles"), "{}", err);
+
+    assert!(parse_binary_stl_with_options(content, &strict).is_ok());
+}
+
+#[test]
+fn test_progress_ends_with_triangle_count() {
+    let model = StlModel::new(String::new(), vec![Triangle::from([[0.0; 3]; 4]); 3000]);
+    let bytes = model.as_binary();
+
+    let mut calls = Vec::new();
+    let parsed = parse_binary_stl_with_progress(&bytes, |parsed, total| {
+        calls.push((parsed, total));
+    }).unwrap();
+
+    assert_eq!(parsed, model);
+    assert_eq!(calls, vec![(0, 3000), (1024, 3000), (2048, 3000), (3000, 3000)]);
+}
+
+#[test]
+fn test_parse_binary_into_reuses_model() {
+    let cube = include_bytes!("../../tests/test_cube.stl");
+
+    let mut small = parse_binary_stl(cube).unwrap();
+    small.header = "small".to_string();
+    small.triangles.truncate(3);
+    let small_bytes = small.as_binary();
+
+    let mut model = StlModel::new("", vec![]);
+
+    parse_binary_into(cube, &mut model).unwrap();
+    assert_eq!(model, parse_binary_stl(cube).unwrap());
+    let allocation = model.triangles.as_ptr();
+
+    parse_binary_into(&small_bytes, &mut model).unwrap();
+    assert_eq!(model, small);
+    assert_eq!(model.source_format(), Some(StlFormat::Binary));
+    assert_eq!(model.triangles.as_ptr(), allocation);
+}
+
+#[test]
+fn test_parse_big_endian_cube() {
+    let content = include_bytes!("../../tests/test_cube.stl");
+    let mut cube = parse_binary_stl(content).unwrap();
+    cube.triangles[4].attribute = 0x1234;
+    let little = cube.as_binary();
+
+    let big = swap_byte_order(&little);
+    assert_eq!(&big[..80], &little[..80]);
+    assert_eq!(&big[80..84], &12u32.to_be_bytes());
+    assert_eq!(&big[84 + 4 * 50 + 48..84 + 5 * 50], &0x1234u16.to_be_bytes());
+    assert_eq!(swap_byte_order(&big), little);
+
+    assert_eq!(parse_binary_stl_endian(&big, Endianness::Big).unwrap(), cube);
+    assert_eq!(parse_binary_stl_endian(&little, Endianness::Little).unwrap(), cube);
+    assert_eq!(parse_binary_stl_endian(&little, Endianness::default()).unwrap(), cube);
+
+    // Read the wrong way round, the triangle count is far too large.
+    assert!(parse_binary_stl_endian(&big, Endianness::Little).is_err());
+
+    // Truncated files are swapped as far as they go and then rejected.
+    assert!(parse_binary_stl_endian(&big[..100], Endianness::Big).is_err());
+    assert!(parse_binary_stl_endian(&big[..50], Endianness::Big).is_err());
+}
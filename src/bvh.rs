@@ -0,0 +1,331 @@
+use crate::prelude::*;
+
+use crate::StlModel;
+use crate::geometry::{BoundingBox, Triangle, Vec3};
+use crate::query::{closest_point, ray_distance};
+
+#[cfg(test)]
+mod tests;
+
+/// The most triangles kept together in one leaf of the tree.
+const LEAF_SIZE: usize = 4;
+
+/// A bounding volume hierarchy over the triangles of a model, for answering
+/// many spatial queries quickly.
+///
+/// [`StlModel::raycast`], [`StlModel::triangles_in_box`], and
+/// [`StlModel::distance_to_point`] check every triangle each time they are
+/// called. Building a `TriangleBvh` takes a bit longer than one of those
+/// queries, but after that each query only looks at the triangles near the
+/// ray, box, or point. It pays off when making many queries on
+/// the same model.
+///
+/// The tree keeps its own copy of the triangles, so it does not see changes
+/// made to the model after it was built.
+///
+/// # Examples
+///
+/// ```
+/// use pk_stl::{parse_stl, TriangleBvh};
+///
+/// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+/// let bvh = TriangleBvh::new(&model);
+///
+/// for x in 1..10 {
+///     let origin = [x as f32, 5.0, 20.0].into();
+///     let down = [0.0, 0.0, -1.0].into();
+///
+///     assert_eq!(bvh.raycast(origin, down), model.raycast(origin, down));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TriangleBvh {
+    /// The triangles of the model, ordered so that each node covers a
+    /// contiguous range of them.
+    triangles: Vec<Triangle>,
+    /// The index in the model of each triangle in `triangles`.
+    indices: Vec<usize>,
+    /// The nodes of the tree, with the root first.
+    nodes: Vec<Node>,
+    /// How far to grow the bounds of each node when testing rays against
+    /// them, so rounding can't cause a hit to be missed.
+    padding: f32
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    /// Bounds around every vertex and centroid of the triangles in the node.
+    bounds: BoundingBox,
+    contents: Contents
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Contents {
+    /// A range of `TriangleBvh::triangles`.
+    Leaf { start: usize, end: usize },
+    /// The indices of the two child nodes.
+    Branch { left: usize, right: usize }
+}
+
+/// A triangle waiting to be placed in the tree.
+struct Item {
+    index: usize,
+    bounds: BoundingBox,
+    centroid: Vec3
+}
+
+impl TriangleBvh {
+    /// Build the tree for the triangles of a model.
+    pub fn new(model: &StlModel) -> TriangleBvh {
+        let mut items: Vec<Item> = model.triangles.iter()
+            .enumerate()
+            .map(|(index, triangle)| {
+                let centroid = triangle.centroid();
+                let bounds = triangle.vertices.iter()
+                    .fold(BoundingBox { min: centroid, max: centroid }, |bounds, &v| grow(bounds, v));
+
+                Item { index, bounds, centroid }
+            })
+            .collect();
+
+        let mut nodes = Vec::new();
+        if !items.is_empty() {
+            build(&mut nodes, &mut items, 0);
+        }
+
+        let padding = nodes.first()
+            .map(|root: &Node| {
+                let largest = root.bounds.min.as_arr().iter()
+                    .chain(root.bounds.max.as_arr().iter())
+                    .fold(0.0f32, |largest, value| largest.max(value.abs()));
+
+                largest.max(1.0) * 1e-5
+            })
+            .unwrap_or(0.0);
+
+        TriangleBvh {
+            triangles: items.iter().map(|item| model.triangles[item.index]).collect(),
+            indices: items.iter().map(|item| item.index).collect(),
+            nodes,
+            padding
+        }
+    }
+
+    /// Find the first triangle hit by a ray.
+    ///
+    /// This gives the same result as [`StlModel::raycast`] on the model the
+    /// tree was built from.
+    pub fn raycast(&self, origin: Vec3, direction: Vec3) -> Option<(usize, Vec3)> {
+        let mut nearest: Option<(usize, f32)> = None;
+        let mut stack = Vec::new();
+
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+
+            // Skip nodes the ray misses, or only reaches past the nearest hit.
+            match ray_entry(&node.bounds, origin, direction, self.padding) {
+                Some(entry) if nearest.is_none_or(|(_, t)| entry <= t) => {}
+                _ => continue
+            }
+
+            match node.contents {
+                Contents::Leaf { start, end } => {
+                    for i in start..end {
+                        let Some(t) = ray_distance(&self.triangles[i], origin, direction) else {
+                            continue;
+                        };
+                        let index = self.indices[i];
+
+                        // Ties go to the lowest index, as with a linear search.
+                        let closer = match nearest {
+                            Some((nearest_index, nearest_t)) => t < nearest_t || (t == nearest_t && index < nearest_index),
+                            None => true
+                        };
+                        if closer {
+                            nearest = Some((index, t));
+                        }
+                    }
+                }
+                Contents::Branch { left, right } => {
+                    stack.push(right);
+                    stack.push(left);
+                }
+            }
+        }
+
+        nearest.map(|(i, t)| (i, origin + direction * t))
+    }
+
+    /// Find the triangles whose centres are inside a box.
+    ///
+    /// This gives the same indices, in the same order, as
+    /// [`StlModel::triangles_in_box`] on the model the tree was built from.
+    pub fn intersect_box(&self, bbox: &BoundingBox) -> Vec<usize> {
+        let mut found = Vec::new();
+        let mut stack = Vec::new();
+
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+
+            if !overlaps(&node.bounds, bbox) {
+                continue;
+            }
+
+            match node.contents {
+                Contents::Leaf { start, end } => {
+                    for i in start..end {
+                        if bbox.contains(self.triangles[i].centroid()) {
+                            found.push(self.indices[i]);
+                        }
+                    }
+                }
+                Contents::Branch { left, right } => {
+                    stack.push(right);
+                    stack.push(left);
+                }
+            }
+        }
+
+        found.sort_unstable();
+        found
+    }
+
+    /// The distance from a point to the nearest point on the surface of the
+    /// model.
+    ///
+    /// This gives the same result as [`StlModel::distance_to_point`] on the
+    /// model the tree was built from, including infinity for a model with no
+    /// triangles.
+    pub fn distance_to_point(&self, p: Vec3) -> f32 {
+        let mut nearest = f32::INFINITY;
+        let mut stack = Vec::new();
+
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+
+            // Skip nodes that are all further away than the nearest point.
+            if box_distance(&node.bounds, p) - self.padding > nearest {
+                continue;
+            }
+
+            match node.contents {
+                Contents::Leaf { start, end } => {
+                    for triangle in &self.triangles[start..end] {
+                        nearest = nearest.min(closest_point(triangle, p).distance(p));
+                    }
+                }
+                Contents::Branch { left, right } => {
+                    // Visit the nearer child first, so the other is more
+                    // likely to be skipped.
+                    let (near, far) = if box_distance(&self.nodes[left].bounds, p) <= box_distance(&self.nodes[right].bounds, p) {
+                        (left, right)
+                    } else {
+                        (right, left)
+                    };
+
+                    stack.push(far);
+                    stack.push(near);
+                }
+            }
+        }
+
+        nearest
+    }
+}
+
+/// Add a node for the items, splitting it in two if there are too many.
+///
+/// The items are reordered so that each node covers a contiguous range, where
+/// `start` is the position of the first item in the whole list. Returns the
+/// index of the new node.
+fn build(nodes: &mut Vec<Node>, items: &mut [Item], start: usize) -> usize {
+    let bounds = items.iter()
+        .map(|item| item.bounds)
+        .reduce(merge)
+        .expect("nodes are never built for no items");
+
+    let index = nodes.len();
+    nodes.push(Node { bounds, contents: Contents::Leaf { start, end: start + items.len() } });
+
+    if items.len() > LEAF_SIZE {
+        // Split at the middle centroid along the axis they are most spread
+        // out on.
+        let centroids = items.iter()
+            .map(|item| BoundingBox { min: item.centroid, max: item.centroid })
+            .reduce(merge)
+            .expect("there are items");
+        let spread = centroids.size();
+        let axis = (0..3).max_by(|&a, &b| spread[a].total_cmp(&spread[b])).unwrap_or(0);
+
+        let middle = items.len() / 2;
+        items.select_nth_unstable_by(middle, |a, b| a.centroid[axis].total_cmp(&b.centroid[axis]));
+        let (low, high) = items.split_at_mut(middle);
+
+        let left = build(nodes, low, start);
+        let right = build(nodes, high, start + middle);
+        nodes[index].contents = Contents::Branch { left, right };
+    }
+
+    index
+}
+
+fn grow(bounds: BoundingBox, point: Vec3) -> BoundingBox {
+    merge(bounds, BoundingBox { min: point, max: point })
+}
+
+fn merge(a: BoundingBox, b: BoundingBox) -> BoundingBox {
+    BoundingBox {
+        min: Vec3::new([a.min.x.min(b.min.x), a.min.y.min(b.min.y), a.min.z.min(b.min.z)]),
+        max: Vec3::new([a.max.x.max(b.max.x), a.max.y.max(b.max.y), a.max.z.max(b.max.z)])
+    }
+}
+
+fn overlaps(a: &BoundingBox, b: &BoundingBox) -> bool {
+    (0..3).all(|axis| a.min[axis] <= b.max[axis] && b.min[axis] <= a.max[axis])
+}
+
+/// The distance from a point to the nearest point in a box, which is zero
+/// for points inside it.
+fn box_distance(bounds: &BoundingBox, p: Vec3) -> f32 {
+    let gap = |axis: usize| (bounds.min[axis] - p[axis]).max(p[axis] - bounds.max[axis]).max(0.0);
+
+    Vec3::new([gap(0), gap(1), gap(2)]).length()
+}
+
+/// How many lengths of `direction` along the ray it enters the box, grown by
+/// `padding` on every side, or `None` if it misses the box.
+fn ray_entry(bounds: &BoundingBox, origin: Vec3, direction: Vec3, padding: f32) -> Option<f32> {
+    let mut entry = 0.0f32;
+    let mut exit = f32::INFINITY;
+
+    for axis in 0..3 {
+        let min = bounds.min[axis] - padding;
+        let max = bounds.max[axis] + padding;
+
+        if direction[axis] == 0.0 {
+            if origin[axis] < min || origin[axis] > max {
+                return None;
+            }
+        } else {
+            let inverse = 1.0 / direction[axis];
+            let (a, b) = ((min - origin[axis]) * inverse, (max - origin[axis]) * inverse);
+
+            entry = entry.max(a.min(b));
+            exit = exit.min(a.max(b));
+        }
+    }
+
+    (entry <= exit).then_some(entry)
+}
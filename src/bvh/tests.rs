@@ -0,0 +1,116 @@
+use crate::{parse_stl, StlModel, TriangleBvh};
+use crate::geometry::{BoundingBox, Vec3};
+
+/// A simple xorshift generator, so the tests are repeatable.
+struct Random(u32);
+
+impl Random {
+    /// A number from `low` to `high`.
+    fn next(&mut self, low: f32, high: f32) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+
+        low + (high - low) * (self.0 as f32 / u32::MAX as f32)
+    }
+
+    fn point(&mut self, low: f32, high: f32) -> Vec3 {
+        Vec3::new([self.next(low, high), self.next(low, high), self.next(low, high)])
+    }
+}
+
+fn models() -> Vec<StlModel> {
+    let cube = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    let mut subdivided = cube.clone();
+    subdivided.subdivide(3);
+
+    vec![cube, subdivided]
+}
+
+#[test]
+fn test_raycast_matches_brute_force() {
+    let mut random = Random(0x2545_f491);
+
+    for model in models() {
+        let bvh = TriangleBvh::new(&model);
+        let mut hits = 0;
+
+        for _ in 0..500 {
+            let origin = random.point(-5.0, 15.0);
+            // Aim roughly at the cube so most rays hit it.
+            let direction = random.point(2.0, 8.0) - origin;
+
+            let expected = model.raycast(origin, direction);
+            assert_eq!(bvh.raycast(origin, direction), expected);
+
+            hits += expected.is_some() as usize;
+        }
+
+        assert!(hits > 250);
+    }
+}
+
+#[test]
+fn test_raycast_along_axes_matches_brute_force() {
+    for model in models() {
+        let bvh = TriangleBvh::new(&model);
+
+        // Rays along the faces and edges of the cube, with zero components.
+        for x in 0..=10 {
+            for y in 0..=10 {
+                let origin = [x as f32, y as f32, 20.0].into();
+                let down = [0.0, 0.0, -1.0].into();
+
+                assert_eq!(bvh.raycast(origin, down), model.raycast(origin, down));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_intersect_box_matches_brute_force() {
+    let mut random = Random(0x9e37_79b9);
+
+    for model in models() {
+        let bvh = TriangleBvh::new(&model);
+
+        for _ in 0..200 {
+            let (a, b) = (random.point(-2.0, 12.0), random.point(-2.0, 12.0));
+            let bbox = BoundingBox {
+                min: [a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)].into(),
+                max: [a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)].into()
+            };
+
+            assert_eq!(bvh.intersect_box(&bbox), model.triangles_in_box(&bbox));
+        }
+
+        let everything = model.bounding_box().unwrap();
+        assert_eq!(bvh.intersect_box(&everything), (0..model.triangles.len()).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn test_empty_model() {
+    let bvh = TriangleBvh::new(&StlModel::new("empty", vec![]));
+    let bbox = BoundingBox { min: [0.0; 3].into(), max: [1.0; 3].into() };
+
+    assert_eq!(bvh.raycast([0.0; 3].into(), [1.0, 0.0, 0.0].into()), None);
+    assert!(bvh.intersect_box(&bbox).is_empty());
+    assert_eq!(bvh.distance_to_point([0.0; 3].into()), f32::INFINITY);
+}
+
+#[test]
+fn test_distance_to_point_matches_brute_force() {
+    let mut random = Random(0x1234_5678);
+
+    for model in models() {
+        let bvh = TriangleBvh::new(&model);
+
+        for _ in 0..200 {
+            let point = random.point(-5.0, 15.0);
+
+            assert_eq!(bvh.distance_to_point(point), model.distance_to_point(point), "{:?}", point);
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use crate::StlModel;
+use crate::geometry::Triangle;
+use crate::prelude::*;
+
+#[cfg(test)]
+mod tests;
+
+/// The differences between two models found by [`StlModel::diff`].
+///
+/// Each field lists the indices of the triangles that changed. Triangles that
+/// are in both models, even at different indices, are not listed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModelDiff {
+    /// Indices in the new model of triangles that are not in the old one.
+    pub added: Vec<usize>,
+    /// Indices in the old model of triangles that are not in the new one.
+    pub removed: Vec<usize>,
+    /// Indices of triangles that are in a different place in the new model
+    /// than at the same index in the old model, and are not anywhere in the
+    /// old model.
+    ///
+    /// This is what a transform such as a translation looks like: every
+    /// triangle keeps its index but moves.
+    pub moved: Vec<usize>
+}
+
+impl ModelDiff {
+    /// True if the models have the same triangles.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.moved.is_empty()
+    }
+}
+
+impl StlModel {
+    /// Compare the triangles of this model with those of `other`.
+    ///
+    /// Triangles are matched by position: two triangles are the same if each
+    /// vertex of one is within `epsilon` of the matching vertex of the other,
+    /// starting from any vertex but going around in the same direction. Each
+    /// triangle matches at most one triangle of the other model. Normals and
+    /// attributes are not compared.
+    ///
+    /// Triangles of this model left without a match are compared by index:
+    /// if `other` has a triangle at the same index that also has no match,
+    /// the triangle [moved](ModelDiff::moved). The rest were
+    /// [removed](ModelDiff::removed) from this model or
+    /// [added](ModelDiff::added) in `other`.
+    ///
+    /// This is useful for checking that a change to a model did only what
+    /// was expected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// let mut edited = model.clone();
+    /// edited.triangles.swap(0, 5);
+    /// edited.triangles.pop();
+    ///
+    /// let diff = model.diff(&edited, 1e-6);
+    ///
+    /// assert!(diff.added.is_empty());
+    /// assert_eq!(diff.removed, vec![11]);
+    /// assert!(diff.moved.is_empty());
+    /// ```
+    pub fn diff(&self, other: &StlModel, epsilon: f32) -> ModelDiff {
+        // Sort the other triangles by their lowest x, so only those with a
+        // close enough one need to be checked for each triangle. Unlike the
+        // centroid, the lowest x doesn't depend on which vertex comes first,
+        // and it moves by no more than `epsilon` when the vertices do.
+        let mut candidates: Vec<(f32, usize)> = other.triangles.iter()
+            .enumerate()
+            .map(|(i, triangle)| (min_x(triangle), i))
+            .collect();
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut matched_other = vec![false; other.triangles.len()];
+        let mut unmatched = Vec::new();
+
+        for (i, triangle) in self.triangles.iter().enumerate() {
+            let x = min_x(triangle);
+            let start = candidates.partition_point(|&(other_x, _)| other_x < x - epsilon);
+
+            let found = candidates[start..].iter()
+                .take_while(|&&(other_x, _)| other_x <= x + epsilon)
+                .map(|&(_, j)| j)
+                .find(|&j| !matched_other[j] && same_position(triangle, &other.triangles[j], epsilon));
+
+            match found {
+                Some(j) => matched_other[j] = true,
+                None => unmatched.push(i)
+            }
+        }
+
+        let mut diff = ModelDiff::default();
+
+        for i in unmatched {
+            if matched_other.get(i) == Some(&false) {
+                matched_other[i] = true;
+                diff.moved.push(i);
+            } else {
+                diff.removed.push(i);
+            }
+        }
+
+        diff.added = matched_other.iter()
+            .enumerate()
+            .filter(|(_, &matched)| !matched)
+            .map(|(i, _)| i)
+            .collect();
+
+        diff
+    }
+}
+
+/// The lowest x coordinate of the vertices of a triangle.
+fn min_x(triangle: &Triangle) -> f32 {
+    triangle.vertices.iter().map(|v| v.x).fold(f32::INFINITY, f32::min)
+}
+
+/// Whether the vertices of two triangles are within `epsilon` of each other,
+/// allowing the second to start from a different vertex.
+fn same_position(a: &Triangle, b: &Triangle, epsilon: f32) -> bool {
+    (0..3).any(|rotation| {
+        (0..3).all(|i| a.vertices[i].distance(b.vertices[(i + rotation) % 3]) <= epsilon)
+    })
+}
@@ -0,0 +1,85 @@
+use crate::{parse_stl, StlModel};
+use crate::geometry::{Triangle, Vec3};
+
+#[test]
+fn test_diff_of_identical_models_is_empty() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let mut reordered = model.clone();
+    reordered.triangles.reverse();
+    reordered.triangles[3].vertices.rotate_left(1);
+
+    assert!(model.diff(&model, 0.0).is_empty());
+    assert!(model.diff(&reordered, 0.0).is_empty());
+}
+
+#[test]
+fn test_diff_ignores_starting_vertex() {
+    // Non-integer coordinates, where the centroid can round differently
+    // depending on which vertex comes first.
+    let mut random = 0x9e37_79b9u32;
+    let mut next = || {
+        random ^= random << 13;
+        random ^= random >> 17;
+        random ^= random << 5;
+
+        random as f32 / u32::MAX as f32 * 200.0 - 100.0
+    };
+
+    let triangles: Vec<Triangle> = (0..1000)
+        .map(|_| Triangle::from([
+            [next(), next(), next()],
+            [next(), next(), next()],
+            [next(), next(), next()],
+            [0.0, 0.0, 1.0]
+        ]))
+        .collect();
+    let model = StlModel::new("random", triangles);
+
+    let mut rotated = model.clone();
+    for (i, triangle) in rotated.triangles.iter_mut().enumerate() {
+        triangle.vertices.rotate_left(1 + i % 2);
+    }
+
+    let diff = model.diff(&rotated, 0.0);
+    assert!(diff.is_empty(), "{:?}", diff);
+}
+
+#[test]
+fn test_diff_against_translated_copy() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let mut moved = model.clone();
+    moved.map_vertices(|v| v + Vec3::new([0.0, 0.0, 0.5]));
+
+    let diff = model.diff(&moved, 1e-4);
+    assert!(diff.added.is_empty());
+    assert!(diff.removed.is_empty());
+    assert_eq!(diff.moved, (0..12).collect::<Vec<_>>());
+
+    // Moving less than epsilon isn't a change.
+    assert!(model.diff(&moved, 0.6).is_empty());
+}
+
+#[test]
+fn test_diff_added_and_removed() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    let mut fewer = model.clone();
+    fewer.triangles.remove(4);
+
+    let diff = model.diff(&fewer, 0.0);
+    assert_eq!(diff.removed, vec![4]);
+    assert!(diff.added.is_empty() && diff.moved.is_empty());
+
+    let diff = fewer.diff(&model, 0.0);
+    assert_eq!(diff.added, vec![4]);
+    assert!(diff.removed.is_empty() && diff.moved.is_empty());
+
+    // Flipping a triangle doesn't leave it in the same place.
+    let mut flipped = model.clone();
+    flipped.triangles[2].flip();
+    assert_eq!(model.diff(&flipped, 0.0).moved, vec![2]);
+
+    let empty = StlModel::new("empty", vec![]);
+    assert_eq!(empty.diff(&model, 0.0).added.len(), 12);
+    assert_eq!(model.diff(&empty, 0.0).removed.len(), 12);
+}
@@ -1,23 +1,42 @@
+use crate::prelude::*;
+
 /// The main error type for this crate.
 ///
-/// Provides indication of binary or ascii and the message.
+/// Provides indication of what kind of file the error occurred in and the
+/// message.
 #[derive(Debug, Clone)]
 pub struct Error {
-    /// True if the error was in a binary file, false if it was in an ascii
-    /// file.
-    binary: bool,
+    /// The kind of file or operation the error occurred in.
+    kind: ErrorKind,
     /// The error message.
     message: String
 }
 
+/// The kind of file or operation an [`Error`] occurred in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The error was in a binary STL file.
+    Binary,
+    /// The error was in an ASCII STL file.
+    Ascii,
+    /// The data was not a valid STL file in either format.
+    Format,
+    /// The error was in a Wavefront OBJ file.
+    Obj,
+    /// The file could not be read or written.
+    Io,
+    /// The geometry given was not valid.
+    Geometry
+}
+
 /// The result type for this crate.
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
 
 impl Error {
     /// Create a new error that occurred in a binary file.
     pub fn binary(msg: &str) -> Error {
         Error {
-            binary: true,
+            kind: ErrorKind::Binary,
             message: msg.to_string()
         }
     }
@@ -25,17 +44,67 @@ impl Error {
     /// Create a new error that occurred in an ASCII file.
     pub fn ascii(msg: &str) -> Error {
         Error {
-            binary: false,
+            kind: ErrorKind::Ascii,
+            message: msg.to_string()
+        }
+    }
+
+    /// Create a new error for data that is not valid in any STL format.
+    pub fn format(msg: &str) -> Error {
+        Error {
+            kind: ErrorKind::Format,
             message: msg.to_string()
         }
     }
+
+    /// Create a new error that occurred in an OBJ file.
+    pub fn obj(msg: &str) -> Error {
+        Error {
+            kind: ErrorKind::Obj,
+            message: msg.to_string()
+        }
+    }
+
+    /// Create a new error from a failed read or write.
+    pub fn io(msg: &str) -> Error {
+        Error {
+            kind: ErrorKind::Io,
+            message: msg.to_string()
+        }
+    }
+
+    /// Create a new error for geometry that is not valid.
+    pub fn geometry(msg: &str) -> Error {
+        Error {
+            kind: ErrorKind::Geometry,
+            message: msg.to_string()
+        }
+    }
+
+    /// The kind of file or operation the error occurred in.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
 }
 
-impl std::fmt::Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let bin_or_ascii_str = if self.binary { "Binary" } else { "ASCII" };
-        write!(f, "{} STL Parse Error: {}", bin_or_ascii_str, self.message)
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self.kind {
+            ErrorKind::Binary => write!(f, "Binary STL Parse Error: {}", self.message),
+            ErrorKind::Ascii => write!(f, "ASCII STL Parse Error: {}", self.message),
+            ErrorKind::Format => write!(f, "STL Parse Error: {}", self.message),
+            ErrorKind::Obj => write!(f, "OBJ Parse Error: {}", self.message),
+            ErrorKind::Io => write!(f, "IO Error: {}", self.message),
+            ErrorKind::Geometry => write!(f, "Invalid Geometry: {}", self.message)
+        }
     }
 }
 
-impl std::error::Error for Error {}
\ No newline at end of file
+impl core::error::Error for Error {}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Error {
+        Error::io(&err.to_string())
+    }
+}
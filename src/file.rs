@@ -0,0 +1,51 @@
+use std::path::Path;
+
+use crate::{parse_stl, StlFormat, StlModel};
+use crate::error::Result;
+
+#[cfg(test)]
+mod tests;
+
+/// Read and parse an STL file.
+///
+/// The file can be either ASCII or binary, detected the same way as in
+/// [`parse_stl`]. Errors reading the file are returned as errors of kind
+/// [`ErrorKind::Io`].
+///
+/// This function is only available with the `std` feature.
+///
+/// [`ErrorKind::Io`]: crate::error::ErrorKind::Io
+pub fn parse_stl_file<P: AsRef<Path>>(path: P) -> Result<StlModel> {
+    let bytes = std::fs::read(path)?;
+
+    parse_stl(&bytes)
+}
+
+impl StlModel {
+    /// Write the model to a file in the given format.
+    ///
+    /// The file is created if it does not exist, and replaced if it does.
+    /// See [`StlModel::as_ascii`] and [`StlModel::as_binary`] for the contents
+    /// written in each format.
+    ///
+    /// This method is only available with the `std` feature.
+    pub fn save<P: AsRef<Path>>(&self, path: P, format: StlFormat) -> Result<()> {
+        match format {
+            StlFormat::Ascii => std::fs::write(path, self.as_ascii())?,
+            StlFormat::Binary => std::fs::write(path, self.as_binary())?
+        }
+
+        Ok(())
+    }
+
+    /// Write the model to a file in the format it was parsed from.
+    ///
+    /// This rewrites a file in the same format it was read in. Models that
+    /// were not parsed from an STL file (see [`StlModel::source_format`]) are
+    /// written in the binary format.
+    ///
+    /// This method is only available with the `std` feature.
+    pub fn save_same_format<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.save(path, self.source_format().unwrap_or(StlFormat::Binary))
+    }
+}
@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+
+use super::*;
+use crate::error::ErrorKind;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("pk_stl_{}_{}.stl", name, std::process::id()))
+}
+
+#[test]
+fn test_parse_stl_file() {
+    let content = include_bytes!("../../tests/test_cube.stl");
+    let path = temp_path("parse_file");
+    std::fs::write(&path, content).unwrap();
+
+    let result = parse_stl_file(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(result.unwrap(), parse_stl(content).unwrap());
+}
+
+#[test]
+fn test_parse_missing_file() {
+    let err = parse_stl_file(temp_path("does_not_exist")).unwrap_err();
+
+    assert_eq!(err.kind(), ErrorKind::Io);
+}
+
+#[test]
+fn test_save_and_reparse() {
+    let mut model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    model.header = "cube".to_string();
+
+    for (name, format) in [("save_ascii", StlFormat::Ascii), ("save_binary", StlFormat::Binary)] {
+        let path = temp_path(name);
+        model.save(&path, format).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        let result = parse_stl_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(bytes.starts_with(b"solid "), format == StlFormat::Ascii);
+        assert_eq!(result.unwrap(), model);
+    }
+}
+
+#[test]
+fn test_save_same_format() {
+    let binary = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let ascii: StlModel = binary.as_ascii().parse().unwrap();
+    let generated = StlModel::new("generated", binary.triangles.clone());
+
+    let cases = [
+        ("same_binary", binary, StlFormat::Binary),
+        ("same_ascii", ascii, StlFormat::Ascii),
+        ("same_generated", generated, StlFormat::Binary)
+    ];
+
+    for (name, model, expected_format) in cases {
+        let path = temp_path(name);
+        model.save_same_format(&path).unwrap();
+
+        let result = parse_stl_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(result.unwrap().source_format(), Some(expected_format));
+    }
+}
@@ -1,4 +1,10 @@
-use std::ops::{Add, Sub, Mul};
+use core::ops::{Add, Sub, Mul, Index, IndexMut};
+
+use crate::error::{Error, Result};
+use crate::prelude::*;
+
+#[cfg(test)]
+mod tests;
 
 /// A 3D vector.
 ///
@@ -14,16 +20,39 @@ pub struct Vec3 {
     pub z: f32
 }
 
+/// One of the three coordinate axes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    /// The x axis.
+    X,
+    /// The y axis.
+    Y,
+    /// The z axis.
+    Z
+}
+
+/// An axis-aligned box, given by its lowest and highest corners.
+///
+/// This is usually the smallest box that contains a model; see
+/// [`StlModel::bounding_box`].
+///
+/// [`StlModel::bounding_box`]: crate::StlModel::bounding_box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    /// The corner with the lowest x, y, and z coordinates.
+    pub min: Vec3,
+    /// The corner with the highest x, y, and z coordinates.
+    pub max: Vec3
+}
+
 /// A single triangle in a model.
 ///
 /// This is the base 3D shape of an STL model. It is composed of a normal vector
 /// and three vertices.
 ///
 /// The normal vector is not verified to be correct, and a model file may give
-/// incorrect values. Currently there is no way to verify or calculate the normals
-/// using this library, however v0.4 will include methods
-/// [`verify_normal`] and [`calculate_normal`]. These methods will be able to
-/// verify and calculate normals.
+/// incorrect values. The normal implied by the vertices can be calculated with
+/// [`Triangle::calculate_normal`].
 ///
 /// The triangle can be initialized through an array of four vertices, where the
 /// first 3 are the vertices and the last is the normal vector. This is the
@@ -54,7 +83,18 @@ pub struct Triangle {
     /// The normal value of the triangle. Not verified to be correct.
     pub normal: Vec3,
     /// The three vertices of the triangle.
-    pub vertices: [Vec3; 3]
+    pub vertices: [Vec3; 3],
+    /// The "attribute byte count" field of a binary STL file.
+    ///
+    /// The STL format does not define a meaning for this field and it is
+    /// usually zero, but some programs use it to store a colour for the
+    /// triangle (see [`Triangle::color`]). It is preserved when reading and
+    /// writing binary files, unless [`ParseOptions::attributes`] says
+    /// otherwise. ASCII files have no such field, so it is always zero for
+    /// triangles parsed from ASCII.
+    ///
+    /// [`ParseOptions::attributes`]: crate::ParseOptions::attributes
+    pub attribute: u16
 }
 
 impl Vec3 {
@@ -71,6 +111,365 @@ impl Vec3 {
     pub fn as_arr(&self) -> [f32; 3] {
         [self.x, self.y, self.z]
     }
+
+    /// The dot product of this vector and another.
+    pub fn dot(&self, other: Vec3) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// The cross product of this vector and another.
+    pub fn cross(&self, other: Vec3) -> Vec3 {
+        Vec3 {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x
+        }
+    }
+
+    /// The length (magnitude) of the vector.
+    pub fn length(&self) -> f32 {
+        self.dot(*self).sqrt()
+    }
+
+    /// A vector with the same direction and a length of one.
+    ///
+    /// A zero-length vector has no direction, so it is returned unchanged.
+    pub fn normalize(&self) -> Vec3 {
+        let length = self.length();
+
+        if length == 0.0 {
+            *self
+        } else {
+            *self * (1.0 / length)
+        }
+    }
+
+    /// The distance between this point and another.
+    pub fn distance(&self, other: Vec3) -> f32 {
+        (other - *self).length()
+    }
+
+    /// Interpolate linearly between this vector and another.
+    ///
+    /// A `t` of 0.0 gives this vector and 1.0 gives `other`. Values outside
+    /// that range extrapolate along the same line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::geometry::Vec3;
+    ///
+    /// let a = Vec3::new([0.0, 0.0, 0.0]);
+    /// let b = Vec3::new([2.0, 4.0, 6.0]);
+    ///
+    /// assert_eq!(a.lerp(b, 0.5), Vec3::new([1.0, 2.0, 3.0]));
+    /// ```
+    pub fn lerp(&self, other: Vec3, t: f32) -> Vec3 {
+        *self + (other - *self) * t
+    }
+
+    /// The angle between this vector and another, in radians.
+    ///
+    /// The result is from 0 for vectors pointing the same way to pi for
+    /// vectors pointing opposite ways. It is found from both the sine and the
+    /// cosine of the angle rather than with `acos` alone, which loses most of
+    /// its precision for nearly parallel vectors and can give NaN when
+    /// rounding pushes the cosine just past one. The result is 0 if either
+    /// vector has zero length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::geometry::Vec3;
+    ///
+    /// let up = Vec3::new([0.0, 0.0, 1.0]);
+    /// let tilted = Vec3::new([1.0, 0.0, 1.0]);
+    ///
+    /// assert!((up.angle_between(tilted) - std::f32::consts::FRAC_PI_4).abs() < 1e-6);
+    /// ```
+    pub fn angle_between(&self, other: Vec3) -> f32 {
+        self.cross(other).length().atan2(self.dot(other))
+    }
+
+    /// Get a coordinate by its axis number.
+    ///
+    /// Axis 0 is x, 1 is y, and 2 is z. Returns `None` for any other axis.
+    /// Indexing with `[]` does the same, but panics for other axes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::geometry::Vec3;
+    ///
+    /// let v = Vec3::new([1.0, 2.0, 3.0]);
+    ///
+    /// assert_eq!(v.get(2), Some(3.0));
+    /// assert_eq!(v.get(3), None);
+    /// assert_eq!(v[1], 2.0);
+    /// ```
+    pub fn get(&self, axis: usize) -> Option<f32> {
+        match axis {
+            0 => Some(self.x),
+            1 => Some(self.y),
+            2 => Some(self.z),
+            _ => None
+        }
+    }
+}
+
+impl BoundingBox {
+    /// The length of the box along each axis.
+    pub fn size(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    /// The volume of the box.
+    pub fn volume(&self) -> f32 {
+        let size = self.size();
+
+        size.x * size.y * size.z
+    }
+
+    /// Whether the box has no size along at least one axis.
+    ///
+    /// This is true for the bounds of a flat model, a line, or a single
+    /// point, which have no volume.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::geometry::BoundingBox;
+    ///
+    /// let flat = BoundingBox { min: [0.0, 0.0, 1.0].into(), max: [5.0, 5.0, 1.0].into() };
+    ///
+    /// assert!(flat.is_degenerate());
+    /// ```
+    pub fn is_degenerate(&self) -> bool {
+        self.min.x == self.max.x || self.min.y == self.max.y || self.min.z == self.max.z
+    }
+
+    /// Whether a point is inside the box or on its surface.
+    pub fn contains(&self, point: Vec3) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x)
+            && (self.min.y..=self.max.y).contains(&point.y)
+            && (self.min.z..=self.max.z).contains(&point.z)
+    }
+}
+
+impl Triangle {
+    /// Create a triangle from arrays, checking that every value is finite.
+    ///
+    /// The arrays are in the same order as `From<[[f32; 3]; 4]>`: three
+    /// vertices followed by the normal. Unlike that conversion, this returns
+    /// an error if any value is NaN or infinite, which would otherwise
+    /// silently corrupt calculations such as bounding boxes. Use this for
+    /// input that is not trusted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::geometry::Triangle;
+    ///
+    /// let valid = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    /// assert!(Triangle::try_from_arrays(valid).is_ok());
+    ///
+    /// let invalid = [[0.0, 0.0, 0.0], [f32::NAN, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    /// assert!(Triangle::try_from_arrays(invalid).is_err());
+    /// ```
+    pub fn try_from_arrays(data: [[f32; 3]; 4]) -> Result<Triangle> {
+        for (i, point) in data.iter().enumerate() {
+            if point.iter().any(|value| !value.is_finite()) {
+                let name = if i == 3 { "normal" } else { "vertex" };
+
+                return Err(Error::geometry(&format!(
+                    "Triangle {} {:?} has a non-finite coordinate", name, point
+                )));
+            }
+        }
+
+        Ok(Triangle::from(data))
+    }
+
+    /// Calculate the normal of the triangle from its vertices.
+    ///
+    /// The normal follows the right-hand rule, so it points towards the side
+    /// from which the vertices appear in counter-clockwise order. This is the
+    /// convention used by STL files. The result has a length of one, unless
+    /// the triangle is degenerate (has no area), in which case it is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::geometry::{Triangle, Vec3};
+    ///
+    /// let triangle = Triangle::from([
+    ///     [0.0, 0.0, 0.0],
+    ///     [2.0, 0.0, 0.0],
+    ///     [0.0, 2.0, 0.0],
+    ///     [0.0, 0.0, 0.0]
+    /// ]);
+    ///
+    /// assert_eq!(triangle.calculate_normal(), Vec3::new([0.0, 0.0, 1.0]));
+    /// ```
+    pub fn calculate_normal(&self) -> Vec3 {
+        let [a, b, c] = self.vertices;
+
+        (b - a).cross(c - a).normalize()
+    }
+
+    /// The area of the triangle.
+    pub fn area(&self) -> f32 {
+        let [a, b, c] = self.vertices;
+
+        (b - a).cross(c - a).length() / 2.0
+    }
+
+    /// The centre of the triangle, the average of its three vertices.
+    pub fn centroid(&self) -> Vec3 {
+        let [a, b, c] = self.vertices;
+
+        (a + b + c) * (1.0 / 3.0)
+    }
+
+    /// The plane the triangle lies in, as a point on the plane and the unit
+    /// normal of the plane.
+    ///
+    /// The point is the first vertex, and the normal is the one from
+    /// [`Triangle::calculate_normal`] rather than the stored normal. A point
+    /// `p` is on the plane when `(p - point).dot(normal)` is zero, and that
+    /// value is the signed distance from the plane for any other point,
+    /// positive in front of the triangle.
+    ///
+    /// A degenerate triangle has no plane, so its normal is zero.
+    pub fn plane(&self) -> (Vec3, Vec3) {
+        (self.vertices[0], self.calculate_normal())
+    }
+
+    /// Read a triangle in the layout used by binary STL files.
+    ///
+    /// The 50 bytes are twelve little-endian `f32`s (the normal followed by
+    /// the three vertices, each as x, y, z) and then the little-endian `u16`
+    /// attribute.
+    pub fn from_le_bytes(bytes: &[u8; 50]) -> Triangle {
+        let float = |i: usize| {
+            let start = i * 4;
+            f32::from_le_bytes([bytes[start], bytes[start + 1], bytes[start + 2], bytes[start + 3]])
+        };
+        let vec = |i: usize| Vec3::new([float(i), float(i + 1), float(i + 2)]);
+
+        Triangle {
+            normal: vec(0),
+            vertices: [vec(3), vec(6), vec(9)],
+            attribute: u16::from_le_bytes([bytes[48], bytes[49]])
+        }
+    }
+
+    /// Write the triangle in the layout used by binary STL files.
+    ///
+    /// See [`Triangle::from_le_bytes`] for the layout.
+    pub fn to_le_bytes(&self) -> [u8; 50] {
+        let mut bytes = [0u8; 50];
+
+        let values = [self.normal, self.vertices[0], self.vertices[1], self.vertices[2]]
+            .into_iter()
+            .flat_map(|v| v.as_arr());
+
+        for (chunk, value) in bytes.chunks_exact_mut(4).zip(values) {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+
+        bytes[48..].copy_from_slice(&self.attribute.to_le_bytes());
+
+        bytes
+    }
+
+    /// Turn the triangle to face the opposite way.
+    ///
+    /// This reverses the order of the vertices and negates the normal, so the
+    /// triangle covers the same area but its front and back are swapped.
+    pub fn flip(&mut self) {
+        self.vertices.swap(1, 2);
+        self.normal = self.normal * -1.0;
+    }
+
+    /// The colour of the triangle stored in its attribute field, if any.
+    ///
+    /// This uses the convention of VisCAM and SolidView: the lowest 15 bits
+    /// hold 5 bits each of blue, green, and red (from least to most
+    /// significant), and the highest bit is set when the colour is valid.
+    /// Returns `None` if the valid bit is not set.
+    ///
+    /// The 5-bit channels are expanded to 8 bits, so a colour given to
+    /// [`Triangle::set_color`] comes back with the low 3 bits of each channel
+    /// approximated.
+    pub fn color(&self) -> Option<(u8, u8, u8)> {
+        if self.attribute & COLOR_VALID_BIT == 0 {
+            return None;
+        }
+
+        let expand = |shift: u16| {
+            let value = ((self.attribute >> shift) & 0x1f) as u8;
+            (value << 3) | (value >> 2)
+        };
+
+        Some((expand(10), expand(5), expand(0)))
+    }
+
+    /// Store a colour in the attribute field of the triangle.
+    ///
+    /// See [`Triangle::color`] for the encoding. Each channel keeps only its
+    /// top 5 bits. This overwrites anything else stored in the attribute.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::geometry::Triangle;
+    ///
+    /// let mut triangle = Triangle::from([[0.0; 3]; 4]);
+    /// assert_eq!(triangle.color(), None);
+    ///
+    /// triangle.set_color((255, 0, 0));
+    /// assert_eq!(triangle.color(), Some((255, 0, 0)));
+    /// ```
+    pub fn set_color(&mut self, rgb: (u8, u8, u8)) {
+        let (r, g, b) = rgb;
+
+        self.attribute = COLOR_VALID_BIT
+            | ((r as u16 >> 3) << 10)
+            | ((g as u16 >> 3) << 5)
+            | (b as u16 >> 3);
+    }
+}
+
+/// The bit of [`Triangle::attribute`] that marks the colour as valid.
+const COLOR_VALID_BIT: u16 = 0x8000;
+
+/// The cell of a grid that a point falls in, as integers that can be hashed
+/// and compared exactly.
+///
+/// The grid has cubic cells `spacing` wide, with a corner at the origin. This
+/// is useful for bucketing vertices so that nearby ones can be found without
+/// comparing every pair. Points within `spacing` of each other are in the same
+/// cell or in neighbouring cells, so a search for close points should also
+/// check the 26 cells around the key. `spacing` should be greater than zero.
+///
+/// # Examples
+///
+/// ```
+/// use pk_stl::geometry::quantized_key;
+///
+/// let a = quantized_key([1.02, 2.5, -0.3].into(), 0.5);
+/// let b = quantized_key([1.4, 2.9, -0.01].into(), 0.5);
+///
+/// assert_eq!(a, (2, 5, -1));
+/// assert_eq!(a, b);
+/// ```
+pub fn quantized_key(v: Vec3, spacing: f32) -> (i64, i64, i64) {
+    (
+        (v.x / spacing).floor() as i64,
+        (v.y / spacing).floor() as i64,
+        (v.z / spacing).floor() as i64
+    )
 }
 
 impl From<[f32; 3]> for Vec3 {
@@ -115,6 +514,71 @@ impl Mul<f32> for Vec3 {
     }
 }
 
+/// Component-wise (Hadamard) product.
+///
+/// Each coordinate is multiplied by the matching coordinate of the other
+/// vector, which is useful for scaling by a different factor along each axis.
+/// This is not the dot product, which is [`Vec3::dot`], or the cross product,
+/// which is [`Vec3::cross`].
+///
+/// # Examples
+///
+/// ```
+/// use pk_stl::geometry::Vec3;
+///
+/// let scale = Vec3::new([2.0, 1.0, 0.5]);
+///
+/// assert_eq!(Vec3::new([1.0, 2.0, 4.0]) * scale, Vec3::new([2.0, 2.0, 2.0]));
+/// ```
+impl Mul<Vec3> for Vec3 {
+    type Output = Vec3;
+
+    fn mul(self, other: Vec3) -> Vec3 {
+        Vec3 {
+            x: self.x * other.x,
+            y: self.y * other.y,
+            z: self.z * other.z
+        }
+    }
+}
+
+/// Access a coordinate by its axis number: 0 for x, 1 for y, and 2 for z.
+///
+/// # Panics
+///
+/// Panics if the axis is greater than 2, like indexing past the end of a
+/// slice.
+impl Index<usize> for Vec3 {
+    type Output = f32;
+
+    fn index(&self, axis: usize) -> &f32 {
+        match axis {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("index out of bounds: a Vec3 has 3 axes but the index is {}", axis)
+        }
+    }
+}
+
+/// Mutably access a coordinate by its axis number: 0 for x, 1 for y, and 2
+/// for z.
+///
+/// # Panics
+///
+/// Panics if the axis is greater than 2, like indexing past the end of a
+/// slice.
+impl IndexMut<usize> for Vec3 {
+    fn index_mut(&mut self, axis: usize) -> &mut f32 {
+        match axis {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            2 => &mut self.z,
+            _ => panic!("index out of bounds: a Vec3 has 3 axes but the index is {}", axis)
+        }
+    }
+}
+
 impl From<[[f32; 3]; 4]> for Triangle {
     fn from(data: [[f32; 3]; 4]) -> Self {
         Triangle {
@@ -123,7 +587,105 @@ impl From<[[f32; 3]; 4]> for Triangle {
                 data[0].into(),
                 data[1].into(),
                 data[2].into()
-            ]
+            ],
+            attribute: 0
         }
     }
 }
+
+/// A 3D vector with `f64` coordinates.
+///
+/// STL files store coordinates as `f32`, but calculations that add up many
+/// values, such as volumes of models with large coordinates, can lose a lot
+/// of precision in `f32`. Converting to this type first keeps the rounding
+/// error down. See [`StlModel::to_f64`](crate::StlModel::to_f64).
+///
+/// # Examples
+///
+/// ```
+/// use pk_stl::geometry::{Vec3, Vec3f64};
+///
+/// let v = Vec3f64::from(Vec3::new([3.0, 4.0, 0.0]));
+///
+/// assert_eq!(v.length(), 5.0);
+/// assert_eq!(v.to_f32(), Vec3::new([3.0, 4.0, 0.0]));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vec3f64 {
+    /// The x coordinate of the vector.
+    pub x: f64,
+    /// The y coordinate of the vector.
+    pub y: f64,
+    /// The z coordinate of the vector.
+    pub z: f64
+}
+
+impl Vec3f64 {
+    /// Create a new vector from an array of three values.
+    pub fn new(data: [f64; 3]) -> Vec3f64 {
+        Vec3f64 {
+            x: data[0],
+            y: data[1],
+            z: data[2]
+        }
+    }
+
+    /// Create an array of three values from the vector.
+    pub fn as_arr(&self) -> [f64; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    /// The dot product of this vector and another.
+    pub fn dot(&self, other: Vec3f64) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// The cross product of this vector and another.
+    pub fn cross(&self, other: Vec3f64) -> Vec3f64 {
+        Vec3f64 {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x
+        }
+    }
+
+    /// The length (magnitude) of the vector.
+    pub fn length(&self) -> f64 {
+        self.dot(*self).sqrt()
+    }
+
+    /// Round the coordinates to the nearest `f32`.
+    pub fn to_f32(&self) -> Vec3 {
+        Vec3::new([self.x as f32, self.y as f32, self.z as f32])
+    }
+}
+
+impl From<Vec3> for Vec3f64 {
+    fn from(v: Vec3) -> Vec3f64 {
+        Vec3f64::new([v.x as f64, v.y as f64, v.z as f64])
+    }
+}
+
+impl Add for Vec3f64 {
+    type Output = Vec3f64;
+
+    fn add(self, other: Vec3f64) -> Vec3f64 {
+        Vec3f64::new([self.x + other.x, self.y + other.y, self.z + other.z])
+    }
+}
+
+impl Sub for Vec3f64 {
+    type Output = Vec3f64;
+
+    fn sub(self, other: Vec3f64) -> Vec3f64 {
+        Vec3f64::new([self.x - other.x, self.y - other.y, self.z - other.z])
+    }
+}
+
+impl Mul<f64> for Vec3f64 {
+    type Output = Vec3f64;
+
+    fn mul(self, scalar: f64) -> Vec3f64 {
+        Vec3f64::new([self.x * scalar, self.y * scalar, self.z * scalar])
+    }
+}
@@ -0,0 +1,236 @@
+use super::*;
+use crate::error::ErrorKind;
+
+#[test]
+fn test_try_from_arrays_accepts_finite_values() {
+    let data = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 1.0]
+    ];
+
+    assert_eq!(Triangle::try_from_arrays(data).unwrap(), Triangle::from(data));
+}
+
+#[test]
+fn test_try_from_arrays_rejects_nan() {
+    let data = [
+        [0.0, 0.0, 0.0],
+        [1.0, f32::NAN, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 1.0]
+    ];
+
+    let err = Triangle::try_from_arrays(data).unwrap_err();
+
+    assert_eq!(err.kind(), ErrorKind::Geometry);
+    assert!(err.to_string().contains("vertex"));
+}
+
+#[test]
+fn test_try_from_arrays_rejects_infinite_normal() {
+    let data = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, f32::INFINITY]
+    ];
+
+    let err = Triangle::try_from_arrays(data).unwrap_err();
+
+    assert!(err.to_string().contains("normal"));
+}
+
+#[test]
+fn test_triangle_byte_round_trip() {
+    let mut triangle = Triangle::from([
+        [1.0, 2.0, 3.0],
+        [-4.5, 5.25, 6.0],
+        [7.0, -8.0, 9.125],
+        [0.0, -1.0, 0.0]
+    ]);
+    triangle.attribute = 0xbeef;
+
+    let bytes = triangle.to_le_bytes();
+
+    assert_eq!(&bytes[0..4], &0.0f32.to_le_bytes());
+    assert_eq!(&bytes[4..8], &(-1.0f32).to_le_bytes());
+    assert_eq!(&bytes[12..16], &1.0f32.to_le_bytes());
+    assert_eq!(&bytes[44..48], &9.125f32.to_le_bytes());
+    assert_eq!(&bytes[48..50], &[0xef, 0xbe]);
+
+    assert_eq!(Triangle::from_le_bytes(&bytes), triangle);
+}
+
+#[test]
+fn test_triangle_from_cube_bytes() {
+    let content = include_bytes!("../../tests/test_cube.stl");
+    let bytes: &[u8; 50] = content[84..134].try_into().unwrap();
+
+    let triangle = Triangle::from_le_bytes(bytes);
+
+    assert_eq!(triangle.normal, [0.0, 0.0, 1.0].into());
+    assert_eq!(&triangle.to_le_bytes(), bytes);
+}
+
+#[test]
+fn test_component_wise_mul() {
+    let a = Vec3::new([1.0, -2.0, 3.0]);
+    let b = Vec3::new([4.0, 5.0, -0.5]);
+
+    assert_eq!(a * b, Vec3::new([4.0, -10.0, -1.5]));
+    assert_eq!(a * b, b * a);
+    assert_eq!(a * Vec3::new([2.0, 2.0, 2.0]), a * 2.0);
+}
+
+#[test]
+fn test_vec3_get() {
+    let v = Vec3::new([1.0, 2.0, 3.0]);
+
+    assert_eq!(v.get(0), Some(1.0));
+    assert_eq!(v.get(1), Some(2.0));
+    assert_eq!(v.get(2), Some(3.0));
+    assert_eq!(v.get(3), None);
+    assert_eq!(v.get(usize::MAX), None);
+}
+
+#[test]
+fn test_vec3_index() {
+    let mut v = Vec3::new([1.0, 2.0, 3.0]);
+
+    assert_eq!((v[0], v[1], v[2]), (1.0, 2.0, 3.0));
+
+    for axis in 0..3 {
+        v[axis] *= 10.0;
+    }
+
+    assert_eq!(v, Vec3::new([10.0, 20.0, 30.0]));
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds")]
+fn test_vec3_index_out_of_range() {
+    let v = Vec3::new([1.0, 2.0, 3.0]);
+
+    let _ = v[3];
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds")]
+fn test_vec3_index_mut_out_of_range() {
+    let mut v = Vec3::new([1.0, 2.0, 3.0]);
+
+    v[3] = 4.0;
+}
+
+#[test]
+fn test_vec3_distance() {
+    let a = Vec3::new([1.0, 2.0, 3.0]);
+    let b = Vec3::new([4.0, 6.0, 3.0]);
+
+    assert_eq!(a.distance(b), 5.0);
+    assert_eq!(b.distance(a), 5.0);
+    assert_eq!(a.distance(a), 0.0);
+}
+
+#[test]
+fn test_vec3_lerp() {
+    let a = Vec3::new([1.0, -2.0, 4.0]);
+    let b = Vec3::new([3.0, 2.0, 8.0]);
+
+    assert_eq!(a.lerp(b, 0.5), Vec3::new([2.0, 0.0, 6.0]));
+    assert_eq!(a.lerp(b, 0.0), a);
+    assert_eq!(a.lerp(b, 1.0), b);
+    assert_eq!(a.lerp(b, 2.0), Vec3::new([5.0, 6.0, 12.0]));
+}
+
+#[test]
+fn test_vec3_angle_between() {
+    use core::f32::consts::{FRAC_PI_2, PI};
+
+    let x = Vec3::new([2.0, 0.0, 0.0]);
+    let y = Vec3::new([0.0, 0.5, 0.0]);
+
+    assert!((x.angle_between(y) - FRAC_PI_2).abs() < 1e-6);
+    assert!((y.angle_between(x) - FRAC_PI_2).abs() < 1e-6);
+    assert!((x.angle_between(x * -3.0) - PI).abs() < 1e-6);
+
+    // Parallel vectors whose cosine rounds to just above one.
+    let v = Vec3::new([0.1, 0.2, 0.3]);
+    assert!(v.angle_between(v * 7.0) < 1e-6);
+
+    // Small angles keep their precision.
+    let tilted = Vec3::new([1.0, 1e-4, 0.0]);
+    assert!((Vec3::new([1.0, 0.0, 0.0]).angle_between(tilted) - 1e-4).abs() < 1e-9);
+
+    assert_eq!(x.angle_between(Vec3::new([0.0; 3])), 0.0);
+}
+
+#[test]
+fn test_quantized_key() {
+    let spacing = 0.1;
+    let a = Vec3::new([1.23, -0.45, 7.0]);
+    let b = Vec3::new([1.27, -0.41, 7.05]);
+
+    assert_eq!(quantized_key(a, spacing), (12, -5, 70));
+    assert_eq!(quantized_key(a, spacing), quantized_key(b, spacing));
+
+    // Further than the spacing apart, the keys differ.
+    let c = Vec3::new([1.35, -0.45, 7.0]);
+    assert_ne!(quantized_key(a, spacing), quantized_key(c, spacing));
+
+    // Close points either side of a cell boundary are in neighbouring cells.
+    let (left, right) = (quantized_key([-0.01; 3].into(), 1.0), quantized_key([0.01; 3].into(), 1.0));
+    assert_eq!(left, (-1, -1, -1));
+    assert_eq!(right, (0, 0, 0));
+
+    // The keys can be used in a hash set.
+    let keys: std::collections::HashSet<_> = [a, b, c].iter().map(|&v| quantized_key(v, spacing)).collect();
+    assert_eq!(keys.len(), 2);
+}
+
+#[test]
+fn test_triangle_centroid() {
+    let triangle = Triangle::from([
+        [0.0, 0.0, 0.0],
+        [3.0, 0.0, 0.0],
+        [0.0, 6.0, 3.0],
+        [0.0, 0.0, 1.0]
+    ]);
+
+    assert_eq!(triangle.centroid(), Vec3::new([1.0, 2.0, 1.0]));
+}
+
+#[test]
+fn test_triangle_plane() {
+    let triangle = Triangle::from([
+        [1.0, 2.0, 3.0],
+        [4.0, -1.0, 2.5],
+        [0.5, 7.0, -3.0],
+        [0.0, 0.0, 0.0]
+    ]);
+
+    let (point, normal) = triangle.plane();
+
+    assert!((normal.length() - 1.0).abs() < 1e-6);
+    for vertex in triangle.vertices {
+        assert!((vertex - point).dot(normal).abs() < 1e-5);
+    }
+
+    // Points in front of the triangle are a positive distance away.
+    assert!((triangle.centroid() + normal * 2.0 - point).dot(normal) > 1.99);
+}
+
+#[test]
+fn test_bounding_box_contains() {
+    let bbox = BoundingBox {
+        min: Vec3::new([0.0, 0.0, 0.0]),
+        max: Vec3::new([1.0, 2.0, 3.0])
+    };
+
+    assert!(bbox.contains(Vec3::new([0.5, 1.0, 1.5])));
+    assert!(bbox.contains(Vec3::new([1.0, 2.0, 3.0])));
+    assert!(!bbox.contains(Vec3::new([1.0, 2.0, 3.5])));
+    assert!(!bbox.contains(Vec3::new([-0.1, 1.0, 1.0])));
+}
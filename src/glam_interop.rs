@@ -0,0 +1,41 @@
+use crate::geometry::{Triangle, Vec3};
+
+#[cfg(test)]
+mod tests;
+
+impl From<glam::Vec3> for Vec3 {
+    fn from(other: glam::Vec3) -> Vec3 {
+        Vec3 {
+            x: other.x,
+            y: other.y,
+            z: other.z
+        }
+    }
+}
+
+impl From<Vec3> for glam::Vec3 {
+    fn from(other: Vec3) -> glam::Vec3 {
+        glam::Vec3::new(other.x, other.y, other.z)
+    }
+}
+
+/// The first three values are the vertices and the last is the normal, the
+/// same order as `From<[[f32; 3]; 4]>`.
+impl From<[glam::Vec3; 4]> for Triangle {
+    fn from(data: [glam::Vec3; 4]) -> Triangle {
+        Triangle::from(data.map(|v| v.to_array()))
+    }
+}
+
+/// The first three values are the vertices and the last is the normal, the
+/// same order as `From<[[f32; 3]; 4]>`.
+impl From<Triangle> for [glam::Vec3; 4] {
+    fn from(triangle: Triangle) -> [glam::Vec3; 4] {
+        [
+            triangle.vertices[0].into(),
+            triangle.vertices[1].into(),
+            triangle.vertices[2].into(),
+            triangle.normal.into()
+        ]
+    }
+}
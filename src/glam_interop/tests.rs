@@ -0,0 +1,28 @@
+use super::*;
+
+#[test]
+fn test_vec3_round_trip() {
+    let original = Vec3::new([1.0, -2.0, 3.5]);
+
+    let converted: glam::Vec3 = original.into();
+    assert_eq!(converted, glam::Vec3::new(1.0, -2.0, 3.5));
+
+    let back: Vec3 = converted.into();
+    assert_eq!(back, original);
+}
+
+#[test]
+fn test_triangle_round_trip() {
+    let original = Triangle::from([
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 1.0]
+    ]);
+
+    let converted: [glam::Vec3; 4] = original.into();
+    assert_eq!(converted[3], glam::Vec3::Z);
+
+    let back = Triangle::from(converted);
+    assert_eq!(back, original);
+}
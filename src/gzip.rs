@@ -0,0 +1,28 @@
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+
+use crate::{parse_stl, StlModel};
+use crate::error::{Error, Result};
+
+#[cfg(test)]
+mod tests;
+
+/// Parse a gzip-compressed STL file from bytes.
+///
+/// The data is decompressed and then parsed with [`parse_stl`], so it can
+/// contain either an ASCII or a binary file. Data that is not valid gzip gives
+/// an [`ErrorKind::Io`] error.
+///
+/// This function is only available with the `gzip` feature.
+///
+/// [`ErrorKind::Io`]: crate::error::ErrorKind::Io
+pub fn parse_stl_gz(bytes: &[u8]) -> Result<StlModel> {
+    let mut decompressed = Vec::new();
+
+    GzDecoder::new(bytes)
+        .read_to_end(&mut decompressed)
+        .map_err(|err| Error::io(&format!("Could not decompress gzip data: {}", err)))?;
+
+    parse_stl(&decompressed)
+}
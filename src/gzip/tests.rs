@@ -0,0 +1,33 @@
+use std::io::Write;
+
+use flate2::Compression;
+use flate2::write::GzEncoder;
+
+use super::*;
+use crate::error::ErrorKind;
+
+fn compress(bytes: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes).unwrap();
+
+    encoder.finish().unwrap()
+}
+
+#[test]
+fn test_parse_compressed_cube() {
+    let content = include_bytes!("../../tests/test_cube.stl");
+    let cube = parse_stl(content).unwrap();
+
+    assert_eq!(parse_stl_gz(&compress(content)).unwrap(), cube);
+
+    let ascii = compress(cube.as_ascii().as_bytes());
+    assert_eq!(parse_stl_gz(&ascii).unwrap().triangles, cube.triangles);
+}
+
+#[test]
+fn test_parse_uncompressed_data_is_an_error() {
+    let err = parse_stl_gz(include_bytes!("../../tests/test_cube.stl")).unwrap_err();
+
+    assert_eq!(err.kind(), ErrorKind::Io);
+    assert!(err.to_string().contains("gzip"));
+}
@@ -0,0 +1,112 @@
+use crate::StlModel;
+use crate::geometry::{Triangle, Vec3};
+use crate::prelude::*;
+
+#[cfg(test)]
+mod tests;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+impl StlModel {
+    /// A hash of the triangles of the model, for noticing when it changes.
+    ///
+    /// This covers the vertices, normals, and attributes of every triangle in
+    /// order, but not the header. Models that compare equal have the same
+    /// hash, including when one has `-0.0` where the other has `0.0`.
+    ///
+    /// The hash is the same on every platform and every run of a program, so
+    /// it can be stored alongside a cache and compared later. It is not
+    /// cryptographic, and may change between major versions of this crate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// let mut moved = model.clone();
+    /// moved.map_vertices(|v| v + [1.0, 0.0, 0.0].into());
+    ///
+    /// assert_eq!(model.content_hash(), model.clone().content_hash());
+    /// assert_ne!(model.content_hash(), moved.content_hash());
+    /// ```
+    pub fn content_hash(&self) -> u64 {
+        let mut hash = FNV_OFFSET;
+
+        for triangle in &self.triangles {
+            hash = fnv(hash, &triangle_hash(triangle, triangle.vertices).to_le_bytes());
+        }
+
+        hash
+    }
+
+    /// Like [`StlModel::content_hash`], but ignoring the order of the
+    /// triangles and which vertex each triangle starts from.
+    ///
+    /// Models with the same triangles in a different order have the same
+    /// hash, as do triangles whose vertices are rotated, such as `[a, b, c]`
+    /// and `[b, c, a]`. The direction the vertices go around the triangle is
+    /// still part of the hash, since reversing it turns the triangle to face
+    /// the other way.
+    ///
+    /// This is useful for finding duplicate meshes that were written by
+    /// different programs.
+    pub fn content_hash_unordered(&self) -> u64 {
+        let mut hashes: Vec<u64> = self.triangles.iter()
+            .map(|triangle| {
+                // Start from the lowest vertex so rotations hash the same.
+                let [a, b, c] = triangle.vertices;
+                let rotations = [[a, b, c], [b, c, a], [c, a, b]];
+                let lowest = rotations.into_iter()
+                    .min_by(|x, y| vertex_bits(x).cmp(&vertex_bits(y)))
+                    .unwrap_or(triangle.vertices);
+
+                triangle_hash(triangle, lowest)
+            })
+            .collect();
+        hashes.sort_unstable();
+
+        hashes.iter().fold(FNV_OFFSET, |hash, triangle| fnv(hash, &triangle.to_le_bytes()))
+    }
+}
+
+/// The hash of a triangle with its vertices in the given order.
+fn triangle_hash(triangle: &Triangle, vertices: [Vec3; 3]) -> u64 {
+    let mut hash = FNV_OFFSET;
+
+    for vector in [triangle.normal].iter().chain(&vertices) {
+        for value in vector.as_arr() {
+            hash = fnv(hash, &canonical_bits(value).to_le_bytes());
+        }
+    }
+
+    fnv(hash, &triangle.attribute.to_le_bytes())
+}
+
+fn vertex_bits(vertices: &[Vec3; 3]) -> [u32; 9] {
+    let mut bits = [0; 9];
+
+    for (i, vertex) in vertices.iter().enumerate() {
+        for (axis, value) in vertex.as_arr().into_iter().enumerate() {
+            bits[i * 3 + axis] = canonical_bits(value);
+        }
+    }
+
+    bits
+}
+
+/// The bits of a float, with `-0.0` treated as `0.0`.
+fn canonical_bits(value: f32) -> u32 {
+    (value + 0.0).to_bits()
+}
+
+/// Add bytes to a 64 bit FNV-1a hash.
+fn fnv(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+
+    hash
+}
@@ -0,0 +1,73 @@
+use crate::parse_stl;
+
+/// A small xorshift generator, so shuffles are the same on every run.
+fn shuffle<T>(items: &mut [T], mut seed: u64) {
+    for i in (1..items.len()).rev() {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        items.swap(i, (seed % (i as u64 + 1)) as usize);
+    }
+}
+
+#[test]
+fn test_content_hash_detects_changes() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let hash = model.content_hash();
+
+    let mut renamed = model.clone();
+    renamed.header = "something else".into();
+    assert_eq!(renamed.content_hash(), hash);
+
+    let mut moved = model.clone();
+    moved.triangles[7].vertices[1].z += 0.001;
+    assert_ne!(moved.content_hash(), hash);
+
+    let mut attribute = model.clone();
+    attribute.triangles[0].attribute = 1;
+    assert_ne!(attribute.content_hash(), hash);
+
+    let mut reordered = model.clone();
+    reordered.triangles.swap(0, 1);
+    assert_ne!(reordered.content_hash(), hash);
+}
+
+#[test]
+fn test_content_hash_ignores_sign_of_zero() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let mut negative = model.clone();
+
+    for triangle in &mut negative.triangles {
+        for vertex in &mut triangle.vertices {
+            if vertex.x == 0.0 {
+                vertex.x = -0.0;
+            }
+        }
+    }
+
+    assert_eq!(negative, model);
+    assert_eq!(negative.content_hash(), model.content_hash());
+}
+
+#[test]
+fn test_content_hash_unordered_ignores_triangle_order() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let mut reordered = model.clone();
+    shuffle(&mut reordered.triangles, 0x9e37_79b9_7f4a_7c15);
+
+    for (i, triangle) in reordered.triangles.iter_mut().enumerate() {
+        triangle.vertices.rotate_left(i % 3);
+    }
+
+    assert_ne!(reordered.content_hash(), model.content_hash());
+    assert_eq!(reordered.content_hash_unordered(), model.content_hash_unordered());
+}
+
+#[test]
+fn test_content_hash_unordered_keeps_winding() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let mut flipped = model.clone();
+    flipped.triangles[2].vertices.swap(1, 2);
+
+    assert_ne!(flipped.content_hash_unordered(), model.content_hash_unordered());
+}
@@ -0,0 +1,128 @@
+use alloc::collections::BTreeSet;
+
+use crate::prelude::*;
+
+use crate::StlModel;
+use crate::geometry::{Triangle, Vec3};
+
+#[cfg(test)]
+mod tests;
+
+impl StlModel {
+    /// The convex hull of the vertices of the model.
+    ///
+    /// This is the smallest convex shape that contains every vertex, returned
+    /// as a closed model with a copy of the header. Its triangles face
+    /// outwards and their normals are calculated from their vertices. Flat
+    /// faces of the hull may be split into several triangles, and vertices
+    /// that lie on the hull but are not corners of it may be left out.
+    ///
+    /// If all the vertices lie in a plane, the hull has no volume and the
+    /// returned model has no triangles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// let hull = model.convex_hull();
+    ///
+    /// // The cube is already convex.
+    /// assert_eq!(hull.triangles.len(), 12);
+    /// assert_eq!(hull.volume(), model.volume());
+    /// ```
+    pub fn convex_hull(&self) -> StlModel {
+        let (points, _) = self.to_indexed(0.0);
+
+        let triangles = hull_faces(&points)
+            .into_iter()
+            .map(|[a, b, c]| {
+                let mut triangle = Triangle {
+                    normal: Vec3::new([0.0; 3]),
+                    vertices: [points[a], points[b], points[c]],
+                    attribute: 0
+                };
+                triangle.normal = triangle.calculate_normal();
+                triangle
+            })
+            .collect();
+
+        StlModel::new(self.header.clone(), triangles)
+    }
+}
+
+/// Find the faces of the convex hull of a set of points.
+///
+/// This builds the hull incrementally: starting from a tetrahedron of four
+/// points, each remaining point outside the hull replaces the faces it can
+/// see with a fan of faces connecting it to the edge of the visible region.
+/// Faces are returned as indices into `points`, wound anticlockwise when seen
+/// from outside.
+fn hull_faces(points: &[Vec3]) -> Vec<[usize; 3]> {
+    let Some(first) = points.first().copied() else {
+        return Vec::new();
+    };
+
+    // Points closer than this to a plane are treated as lying on it. It is
+    // relative to the size of the model to allow for rounding errors.
+    let size = points.iter().map(|p| (*p - first).length()).fold(0.0, f32::max);
+    let epsilon = size * 1e-5;
+
+    let farthest = |distance: &dyn Fn(Vec3) -> f32| {
+        (0..points.len())
+            .map(|i| (i, distance(points[i])))
+            .fold((0, 0.0), |best, current| if current.1 > best.1 { current } else { best })
+    };
+
+    // Find four points that are as far apart as possible to start from.
+    let (b, _) = farthest(&|p| (p - first).length());
+    let line = (points[b] - first).normalize();
+    let (c, distance) = farthest(&|p| (p - first).cross(line).length());
+    if distance <= epsilon {
+        return Vec::new();
+    }
+
+    let plane = (points[b] - first).cross(points[c] - first).normalize();
+    let (d, distance) = farthest(&|p| (p - first).dot(plane).abs());
+    if distance <= epsilon {
+        return Vec::new();
+    }
+
+    let a = 0;
+    let mut faces = if (points[d] - first).dot(plane) < 0.0 {
+        vec![[a, b, c], [a, d, b], [b, d, c], [c, d, a]]
+    } else {
+        vec![[a, c, b], [a, b, d], [b, c, d], [c, a, d]]
+    };
+
+    // How far a point is in front of a face.
+    let height = |face: &[usize; 3], p: Vec3| {
+        let [a, b, c] = face.map(|i| points[i]);
+        (b - a).cross(c - a).normalize().dot(p - a)
+    };
+
+    for (i, &point) in points.iter().enumerate() {
+        if i == a || i == b || i == c || i == d {
+            continue;
+        }
+
+        let (visible, hidden): (Vec<[usize; 3]>, Vec<[usize; 3]>) = faces.into_iter()
+            .partition(|face| height(face, point) > epsilon);
+        faces = hidden;
+
+        // Edges of the visible region are the edges of visible faces that
+        // are not shared with another visible face.
+        let edges: BTreeSet<(usize, usize)> = visible.iter()
+            .flat_map(|&[a, b, c]| [(a, b), (b, c), (c, a)])
+            .collect();
+
+        for &(from, to) in &edges {
+            if !edges.contains(&(to, from)) {
+                faces.push([from, to, i]);
+            }
+        }
+    }
+
+    faces
+}
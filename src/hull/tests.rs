@@ -0,0 +1,71 @@
+use crate::{parse_stl, StlModel};
+use crate::geometry::Triangle;
+
+#[test]
+fn test_hull_of_cube_is_cube() {
+    let cube = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let hull = cube.convex_hull();
+
+    assert_eq!(hull.header, cube.header);
+    assert_eq!(hull.triangles.len(), 12);
+    assert!(hull.is_watertight(0.0));
+    assert!(hull.has_consistent_winding(0.0));
+    assert_eq!(hull.volume(), 1000.0);
+    assert_eq!(hull.bounding_box(), cube.bounding_box());
+    assert_eq!(hull.surface_area(), cube.surface_area());
+}
+
+#[test]
+fn test_hull_ignores_inner_points() {
+    let mut model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    // A small triangle inside the cube, and one sticking out of the top.
+    model.triangles.push(Triangle::from([
+        [2.0, 2.0, 2.0],
+        [3.0, 2.0, 2.0],
+        [2.0, 3.0, 2.0],
+        [0.0, 0.0, 1.0]
+    ]));
+    model.triangles.push(Triangle::from([
+        [4.0, 5.0, 10.0],
+        [6.0, 5.0, 10.0],
+        [5.0, 5.0, 15.0],
+        [0.0, -1.0, 0.0]
+    ]));
+
+    let hull = model.convex_hull();
+
+    assert!(hull.is_watertight(0.0));
+    assert!(hull.has_consistent_winding(0.0));
+
+    // The cube plus a pyramid on top, with its peak at (5, 5, 15).
+    let bounds = hull.bounding_box().unwrap();
+    assert_eq!(bounds.max, [10.0, 10.0, 15.0].into());
+    assert!((hull.volume() - (1000.0 + 100.0 * 5.0 / 3.0)).abs() < 1e-3);
+
+    for triangle in &hull.triangles {
+        assert!(triangle.vertices.iter().all(|v| v.z != 2.0));
+        assert_eq!(triangle.normal, triangle.calculate_normal());
+    }
+}
+
+#[test]
+fn test_hull_of_flat_model_is_empty() {
+    let flat = StlModel::new("flat", vec![
+        Triangle::from([
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0]
+        ]),
+        Triangle::from([
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0]
+        ])
+    ]);
+
+    assert!(flat.convex_hull().triangles.is_empty());
+    assert!(StlModel::new("empty", vec![]).convex_hull().triangles.is_empty());
+}
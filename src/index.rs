@@ -0,0 +1,338 @@
+use alloc::collections::BTreeMap;
+
+use crate::prelude::*;
+
+use crate::StlModel;
+use crate::geometry::{quantized_key, Vec3, Vec3f64};
+
+#[cfg(test)]
+mod tests;
+
+impl StlModel {
+    /// Convert the model into an indexed representation.
+    ///
+    /// STL files store every vertex of every triangle separately, so a vertex
+    /// shared by several triangles is repeated for each of them. This welds
+    /// those duplicates together, returning the list of unique vertices and,
+    /// for each triangle, the indices of its three vertices in that list.
+    ///
+    /// Vertices within `epsilon` of each other are treated as the same vertex.
+    /// An `epsilon` of `0.0` only merges vertices that are exactly equal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// let (vertices, faces) = model.to_indexed(0.0);
+    ///
+    /// assert_eq!(vertices.len(), 8);
+    /// assert_eq!(faces.len(), 12);
+    /// ```
+    pub fn to_indexed(&self, epsilon: f32) -> (Vec<Vec3>, Vec<[u32; 3]>) {
+        let mut welder = VertexWelder::new(epsilon);
+
+        let faces = self.triangles.iter()
+            .map(|triangle| {
+                [
+                    welder.insert(triangle.vertices[0]),
+                    welder.insert(triangle.vertices[1]),
+                    welder.insert(triangle.vertices[2])
+                ]
+            })
+            .collect();
+
+        (welder.vertices, faces)
+    }
+
+    /// Convert the model into an indexed representation with smooth normals.
+    ///
+    /// This returns the welded vertices and faces from
+    /// [`StlModel::to_indexed`], along with a normal for each vertex. The
+    /// normal of a vertex is the average of the normals of the triangles
+    /// around it, weighted by their areas, so the model looks smooth rather
+    /// than faceted when rendered. Normals are calculated from the winding of
+    /// the triangles rather than taken from the stored normals.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// let (vertices, faces, normals) = model.vertex_normals(0.0);
+    ///
+    /// assert_eq!(normals.len(), vertices.len());
+    /// assert_eq!(faces.len(), 12);
+    /// ```
+    pub fn vertex_normals(&self, epsilon: f32) -> (Vec<Vec3>, Vec<[u32; 3]>, Vec<Vec3>) {
+        let (vertices, faces) = self.to_indexed(epsilon);
+        let mut normals = vec![Vec3::new([0.0; 3]); vertices.len()];
+
+        for face in &faces {
+            let [a, b, c] = face.map(|i| vertices[i as usize]);
+
+            // The length of the cross product is twice the area of the
+            // triangle, which weights the normal by area.
+            let weighted = (b - a).cross(c - a);
+
+            for &i in face {
+                normals[i as usize] = normals[i as usize] + weighted;
+            }
+        }
+
+        for normal in &mut normals {
+            *normal = normal.normalize();
+        }
+
+        (vertices, faces, normals)
+    }
+
+    /// The positions of the vertices of each triangle, without normals.
+    ///
+    /// Each triangle becomes nine values, the x, y, and z of each of its
+    /// vertices in order. Use [`slice::as_flattened`] on the result for a
+    /// single slice of `9 * triangles.len()` values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// let positions = model.vertex_positions();
+    ///
+    /// assert_eq!(positions.len(), 12);
+    /// assert_eq!(positions.as_flattened().len(), 12 * 9);
+    /// ```
+    pub fn vertex_positions(&self) -> Vec<[f32; 9]> {
+        self.triangles.iter()
+            .map(|triangle| {
+                let [a, b, c] = triangle.vertices;
+                [a.x, a.y, a.z, b.x, b.y, b.z, c.x, c.y, c.z]
+            })
+            .collect()
+    }
+
+    /// The vertices of each triangle with `f64` coordinates.
+    ///
+    /// Converting from `f32` is exact, so this loses nothing, and further
+    /// calculations on the result are more precise than on the model itself.
+    /// Normals are left out since they can be calculated again from the
+    /// vertices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    ///
+    /// // The volume of the cube, summed in double precision.
+    /// let sum: f64 = model.to_f64().iter()
+    ///     .map(|[a, b, c]| a.dot(b.cross(*c)))
+    ///     .sum();
+    /// let volume = sum / 6.0;
+    ///
+    /// assert_eq!(volume, 1000.0);
+    /// ```
+    pub fn to_f64(&self) -> Vec<[Vec3f64; 3]> {
+        self.triangles.iter()
+            .map(|triangle| triangle.vertices.map(Vec3f64::from))
+            .collect()
+    }
+
+    /// Flatten the model into a buffer of vertex positions and normals.
+    ///
+    /// Each vertex of each triangle becomes six values: its position followed
+    /// by the normal of the triangle, as `[vx, vy, vz, nx, ny, nz]`. Vertices
+    /// are not shared between triangles, so every triangle is flat shaded with
+    /// its stored normal. The buffer can be uploaded to the GPU as is, with
+    /// every three vertices drawn as a triangle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// let buffer = model.interleaved_vertex_normal_buffer();
+    ///
+    /// // 12 triangles, with 3 vertices of 6 values each.
+    /// assert_eq!(buffer.len(), 12 * 3 * 6);
+    /// ```
+    pub fn interleaved_vertex_normal_buffer(&self) -> Vec<f32> {
+        let mut buffer = Vec::with_capacity(self.triangles.len() * 3 * 6);
+
+        for triangle in &self.triangles {
+            for vertex in &triangle.vertices {
+                buffer.extend_from_slice(&vertex.as_arr());
+                buffer.extend_from_slice(&triangle.normal.as_arr());
+            }
+        }
+
+        buffer
+    }
+
+    /// Reorder the triangles so that triangles near each other in space are
+    /// near each other in the list.
+    ///
+    /// The triangles are sorted along a Z-order (Morton) curve through their
+    /// centroids, which keeps most neighbouring triangles close together.
+    /// This helps GPUs reuse recently transformed vertices when drawing, and
+    /// makes reading the triangles in order touch less memory at once. The
+    /// triangles themselves are not changed, and triangles in the same place
+    /// keep their relative order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let mut model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// model.subdivide(2);
+    /// let original = model.clone();
+    ///
+    /// model.optimize_draw_order();
+    ///
+    /// assert_eq!(model.triangles.len(), original.triangles.len());
+    /// assert!(model.triangles.iter().all(|triangle| original.triangles.contains(triangle)));
+    /// ```
+    pub fn optimize_draw_order(&mut self) {
+        let Some(bounds) = self.bounding_box() else {
+            return;
+        };
+        let size = bounds.size();
+
+        self.triangles.sort_by_cached_key(|triangle| {
+            let centroid = triangle.centroid();
+            let cell = |axis: usize| {
+                let scaled = if size[axis] > 0.0 {
+                    (centroid[axis] - bounds.min[axis]) / size[axis] * MORTON_MAX as f32
+                } else {
+                    0.0
+                };
+
+                scaled.clamp(0.0, MORTON_MAX as f32) as u32
+            };
+
+            spread_bits(cell(0)) | spread_bits(cell(1)) << 1 | spread_bits(cell(2)) << 2
+        });
+    }
+}
+
+/// The largest cell along each axis of the grid used for Morton codes, which
+/// use 21 bits per axis to fit in a `u64`.
+const MORTON_MAX: u32 = (1 << 21) - 1;
+
+/// Spread the lowest 21 bits of a number out to every third bit of a `u64`.
+fn spread_bits(value: u32) -> u64 {
+    let mut x = value as u64 & 0x1f_ffff;
+
+    x = (x | x << 32) & 0x001f_0000_0000_ffff;
+    x = (x | x << 16) & 0x001f_0000_ff00_00ff;
+    x = (x | x << 8) & 0x100f_00f0_0f00_f00f;
+    x = (x | x << 4) & 0x10c3_0c30_c30c_30c3;
+    x = (x | x << 2) & 0x1249_2492_4924_9249;
+
+    x
+}
+
+/// Incrementally merges vertices that are within an epsilon of each other.
+///
+/// Vertices are bucketed into a grid with cells the size of the epsilon, so
+/// a lookup only has to check the neighbouring cells rather than every vertex
+/// seen so far.
+#[derive(Debug, Clone)]
+pub(crate) struct VertexWelder {
+    epsilon: f32,
+    vertices: Vec<Vec3>,
+    cells: BTreeMap<(i64, i64, i64), Vec<u32>>
+}
+
+impl VertexWelder {
+    pub fn new(epsilon: f32) -> VertexWelder {
+        VertexWelder {
+            epsilon: epsilon.max(0.0),
+            vertices: Vec::new(),
+            cells: BTreeMap::new()
+        }
+    }
+
+    /// Insert a vertex, returning the index of the vertex it was merged with
+    /// or the index of the newly added vertex.
+    pub fn insert(&mut self, vertex: Vec3) -> u32 {
+        if let Some(index) = self.find(vertex) {
+            return index;
+        }
+
+        let index = self.vertices.len() as u32;
+        self.vertices.push(vertex);
+        self.cells.entry(self.cell(vertex)).or_default().push(index);
+
+        index
+    }
+
+    /// The distinct vertices inserted so far, in the order of their indices.
+    pub fn into_vertices(self) -> Vec<Vec3> {
+        self.vertices
+    }
+
+    /// Find the index of a previously inserted vertex within epsilon.
+    pub fn find(&self, vertex: Vec3) -> Option<u32> {
+        let (cx, cy, cz) = self.cell(vertex);
+
+        if self.epsilon == 0.0 {
+            return self.cells.get(&(cx, cy, cz))?.iter()
+                .copied()
+                .find(|&i| self.vertices[i as usize] == vertex);
+        }
+
+        // Equal infinities are the same, even though their difference is
+        // not a number.
+        let close = |a: f32, b: f32| a == b || (a - b).abs() <= self.epsilon;
+
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    // Cells past the ends of the grid, for coordinates that
+                    // are infinite or too large for their cell to be
+                    // counted, don't exist.
+                    let (Some(x), Some(y), Some(z)) = (cx.checked_add(dx), cy.checked_add(dy), cz.checked_add(dz)) else {
+                        continue;
+                    };
+                    let Some(bucket) = self.cells.get(&(x, y, z)) else {
+                        continue;
+                    };
+
+                    let found = bucket.iter().copied().find(|&i| {
+                        let other = self.vertices[i as usize];
+
+                        close(other.x, vertex.x) && close(other.y, vertex.y) && close(other.z, vertex.z)
+                    });
+
+                    if found.is_some() {
+                        return found;
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    fn cell(&self, vertex: Vec3) -> (i64, i64, i64) {
+        if self.epsilon == 0.0 {
+            // Adding zero turns negative zero into positive zero so that both
+            // land in the same cell.
+            (
+                (vertex.x + 0.0).to_bits() as i64,
+                (vertex.y + 0.0).to_bits() as i64,
+                (vertex.z + 0.0).to_bits() as i64
+            )
+        } else {
+            quantized_key(vertex, self.epsilon)
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use super::*;
+use crate::geometry::Triangle;
+
+#[test]
+fn test_to_indexed_shares_vertices() {
+    let model = StlModel::new(String::new(), vec![
+        Triangle::from([
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0]
+        ]),
+        Triangle::from([
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0]
+        ])
+    ]);
+
+    let (vertices, faces) = model.to_indexed(0.0);
+
+    assert_eq!(vertices.len(), 4);
+    assert_eq!(faces, vec![[0, 1, 2], [1, 3, 2]]);
+}
+
+#[test]
+fn test_welder_merges_within_epsilon() {
+    let mut welder = VertexWelder::new(0.01);
+
+    let a = welder.insert([1.0, 1.0, 1.0].into());
+    let b = welder.insert([1.005, 0.996, 1.0].into());
+    let c = welder.insert([1.02, 1.0, 1.0].into());
+
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+    assert_eq!(welder.vertices.len(), 2);
+}
+
+#[test]
+fn test_welder_with_infinite_coordinates() {
+    let mut welder = VertexWelder::new(1e-4);
+
+    let a = welder.insert([f32::INFINITY, 0.0, 0.0].into());
+    let b = welder.insert([f32::INFINITY, 0.0, 0.0].into());
+    let c = welder.insert([f32::NEG_INFINITY, 0.0, f32::NEG_INFINITY].into());
+
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+    assert_eq!(welder.vertices.len(), 2);
+}
+
+#[test]
+fn test_welder_with_huge_coordinates() {
+    let mut welder = VertexWelder::new(1e-4);
+
+    let a = welder.insert([1e15, -1e15, 0.0].into());
+    let b = welder.insert([1e15, -1e15, 0.0].into());
+    let c = welder.insert([1e15, 1e15, 0.0].into());
+
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+
+#[test]
+fn test_welder_treats_negative_zero_as_zero() {
+    let mut welder = VertexWelder::new(0.0);
+
+    let a = welder.insert([0.0, 0.0, 0.0].into());
+    let b = welder.insert([-0.0, 0.0, -0.0].into());
+
+    assert_eq!(a, b);
+}
+
+#[test]
+fn test_interleaved_vertex_normal_buffer() {
+    let model = crate::parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let buffer = model.interleaved_vertex_normal_buffer();
+
+    assert_eq!(buffer.len(), 36 * 6);
+
+    for (triangle, chunk) in model.triangles.iter().zip(buffer.chunks_exact(18)) {
+        for (vertex, values) in triangle.vertices.iter().zip(chunk.chunks_exact(6)) {
+            assert_eq!(values[..3], vertex.as_arr());
+            assert_eq!(values[3..], triangle.normal.as_arr());
+        }
+    }
+}
+
+#[test]
+fn test_vertex_normals_of_cube_corners() {
+    let model = crate::parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let (vertices, faces, normals) = model.vertex_normals(0.0);
+
+    assert_eq!(vertices.len(), 8);
+    assert_eq!(faces.len(), 12);
+    assert_eq!(normals.len(), 8);
+
+    let center = Vec3::new([5.0, 5.0, 5.0]);
+
+    for (vertex, normal) in vertices.iter().zip(&normals) {
+        assert!((normal.length() - 1.0).abs() < 1e-6);
+
+        // Each corner is shared by three faces of the cube, so its normal
+        // points out of the corner, between all three.
+        let outwards = *vertex - center;
+        for axis in 0..3 {
+            assert!(normal[axis] * outwards[axis] > 0.0);
+        }
+    }
+}
+
+#[test]
+fn test_vertex_normals_of_single_triangle() {
+    let model = StlModel::new("triangle", vec![Triangle::from([
+        [0.0, 0.0, 0.0],
+        [2.0, 0.0, 0.0],
+        [0.0, 2.0, 0.0],
+        [0.0, 0.0, 0.0]
+    ])]);
+
+    let (_, _, normals) = model.vertex_normals(0.0);
+
+    assert_eq!(normals, vec![Vec3::new([0.0, 0.0, 1.0]); 3]);
+}
+
+#[test]
+fn test_to_f64_volume_is_more_precise() {
+    let mut model = crate::parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    // Far from the origin, the terms of the volume sum are huge and mostly
+    // cancel out, which loses almost all precision in f32.
+    for triangle in &mut model.triangles {
+        for vertex in &mut triangle.vertices {
+            *vertex = *vertex + Vec3::new([10_000.0, 20_000.0, 30_000.0]);
+        }
+    }
+
+    let sum: f64 = model.to_f64().iter()
+        .map(|[a, b, c]| a.dot(b.cross(*c)))
+        .sum();
+
+    assert_eq!(sum / 6.0, 1000.0);
+    assert!((model.volume() - 1000.0).abs() > 1.0);
+
+    // The conversion is exact.
+    for (triangle, vertices) in model.triangles.iter().zip(model.to_f64()) {
+        assert_eq!(vertices.map(|v| v.to_f32()), triangle.vertices);
+    }
+}
+
+#[test]
+fn test_vertex_positions() {
+    let model = crate::parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let positions = model.vertex_positions();
+
+    assert_eq!(positions.len(), 12);
+
+    let [a, b, c] = model.triangles[0].vertices;
+    assert_eq!(positions[0][..3], a.as_arr());
+    assert_eq!(positions[0][3..6], b.as_arr());
+    assert_eq!(positions[0][6..], c.as_arr());
+
+    for (triangle, values) in model.triangles.iter().zip(positions.as_flattened().chunks_exact(9)) {
+        let expected: Vec<f32> = triangle.vertices.iter().flat_map(|v| v.as_arr()).collect();
+        assert_eq!(values, expected);
+    }
+}
+
+#[test]
+fn test_spread_bits() {
+    assert_eq!(spread_bits(0), 0);
+    assert_eq!(spread_bits(0b1011), 0b001_000_001_001);
+    assert_eq!(spread_bits(MORTON_MAX), 0x1249_2492_4924_9249);
+}
+
+#[test]
+fn test_optimize_draw_order_keeps_triangles() {
+    let mut model = crate::parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    model.subdivide(3);
+
+    // Shuffle the triangles so they start out scattered.
+    let mut seed = 0x9e37_79b9u32;
+    for i in (1..model.triangles.len()).rev() {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        model.triangles.swap(i, seed as usize % (i + 1));
+    }
+    let shuffled = model.clone();
+
+    model.optimize_draw_order();
+
+    assert_ne!(model.triangles, shuffled.triangles);
+    assert_eq!(model.triangles.len(), shuffled.triangles.len());
+
+    let key = |triangle: &Triangle| triangle.to_le_bytes();
+    let mut sorted = model.triangles.clone();
+    let mut expected = shuffled.triangles.clone();
+    sorted.sort_by_key(key);
+    expected.sort_by_key(key);
+    assert_eq!(sorted, expected);
+
+    // Neighbouring triangles in the list are now close together on average.
+    let spread = |triangles: &[Triangle]| -> f32 {
+        triangles.windows(2).map(|pair| pair[0].centroid().distance(pair[1].centroid())).sum()
+    };
+    assert!(spread(&model.triangles) < spread(&shuffled.triangles) / 3.0);
+
+    // Sorting again changes nothing.
+    let optimized = model.clone();
+    model.optimize_draw_order();
+    assert_eq!(model, optimized);
+}
+
+#[test]
+fn test_optimize_draw_order_of_empty_model() {
+    let mut model = StlModel::new("empty", vec![]);
+    model.optimize_draw_order();
+
+    assert!(model.triangles.is_empty());
+}
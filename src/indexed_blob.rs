@@ -0,0 +1,202 @@
+//! A binary format storing welded vertices and indices, for faster reloading.
+//!
+//! This is not a standard STL format and other programs can't read it. STL
+//! files have no index table, so every time one is loaded the vertices need
+//! welding again before they can be used as an indexed mesh. This format
+//! stores the result of [`StlModel::to_indexed`] instead, so it is only meant
+//! as a cache between programs that both use this crate, written with
+//! [`StlModel::as_indexed_blob`] and read with [`parse_indexed_blob`] or
+//! [`parse_indexed_blob_mesh`].
+//!
+//! # Layout
+//!
+//! All numbers are little-endian.
+//!
+//! | Bytes              | Contents                                            |
+//! |--------------------|-----------------------------------------------------|
+//! | 6                  | The magic bytes `PKSTLI`                            |
+//! | 1                  | The format version, currently 1                     |
+//! | 4                  | The number of vertices, as a `u32`                  |
+//! | 4                  | The number of triangles, as a `u32`                 |
+//! | 4                  | The length of the model header in bytes, as a `u32` |
+//! | header length      | The model header, as UTF-8                          |
+//! | 12 per vertex      | The vertices, as three `f32`s each                  |
+//! | 26 per triangle    | The triangles                                       |
+//!
+//! Each triangle is stored as the indices of its three vertices as `u32`s,
+//! then its normal as three `f32`s, then its attribute as a `u16`.
+
+use crate::prelude::*;
+
+use crate::StlModel;
+use crate::error::{Error, Result};
+use crate::geometry::{Triangle, Vec3};
+use crate::io_util::ByteCursor;
+
+#[cfg(test)]
+mod tests;
+
+const MAGIC: &[u8; 6] = b"PKSTLI";
+const VERSION: u8 = 1;
+const BYTES_PER_VERTEX: usize = 12;
+const BYTES_PER_TRIANGLE: usize = 26;
+
+impl StlModel {
+    /// Convert the model to a binary format with welded vertices.
+    ///
+    /// The vertices are welded with [`StlModel::to_indexed`], using the given
+    /// `epsilon`, and stored once each along with the indices of each
+    /// triangle. Normals and attributes are kept, so with an `epsilon` of
+    /// `0.0` the model reads back exactly as it was.
+    ///
+    /// This is **not** an STL file; see the
+    /// [`indexed_blob`](crate::indexed_blob) module for the layout. Read it
+    /// back with [`parse_indexed_blob`] or [`parse_indexed_blob_mesh`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    /// use pk_stl::indexed_blob::parse_indexed_blob;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    ///
+    /// let blob = model.as_indexed_blob(0.0);
+    /// assert!(blob.len() < model.as_binary().len());
+    ///
+    /// let parsed = parse_indexed_blob(&blob).unwrap();
+    /// assert_eq!(parsed.triangles, model.triangles);
+    /// ```
+    pub fn as_indexed_blob(&self, epsilon: f32) -> Vec<u8> {
+        let (vertices, faces) = self.to_indexed(epsilon);
+        let header = self.header.as_bytes();
+
+        let mut result = Vec::with_capacity(
+            19 + header.len() + vertices.len() * BYTES_PER_VERTEX + faces.len() * BYTES_PER_TRIANGLE
+        );
+        result.extend_from_slice(MAGIC);
+        result.push(VERSION);
+        result.extend_from_slice(&(vertices.len() as u32).to_le_bytes());
+        result.extend_from_slice(&(faces.len() as u32).to_le_bytes());
+        result.extend_from_slice(&(header.len() as u32).to_le_bytes());
+        result.extend_from_slice(header);
+
+        for vertex in &vertices {
+            for value in vertex.as_arr() {
+                result.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+
+        for (face, triangle) in faces.iter().zip(&self.triangles) {
+            for index in face {
+                result.extend_from_slice(&index.to_le_bytes());
+            }
+            for value in triangle.normal.as_arr() {
+                result.extend_from_slice(&value.to_le_bytes());
+            }
+            result.extend_from_slice(&triangle.attribute.to_le_bytes());
+        }
+
+        result
+    }
+}
+
+/// Parse a model written by [`StlModel::as_indexed_blob`].
+///
+/// The model has no [source format](StlModel::source_format), since it was
+/// not parsed from an STL file. Errors are reported as binary errors.
+pub fn parse_indexed_blob(bytes: &[u8]) -> Result<StlModel> {
+    let blob = read_blob(bytes)?;
+
+    let triangles = blob.faces.iter()
+        .map(|(face, normal, attribute)| Triangle {
+            normal: *normal,
+            vertices: face.map(|index| blob.vertices[index as usize]),
+            attribute: *attribute
+        })
+        .collect();
+
+    Ok(StlModel::new(blob.header, triangles))
+}
+
+/// Parse the welded vertices and indices written by
+/// [`StlModel::as_indexed_blob`], without welding them again.
+///
+/// This returns the same vertices and faces as [`StlModel::to_indexed`] did
+/// when the blob was written. The header, normals, and attributes are not
+/// returned; use [`parse_indexed_blob`] for those.
+///
+/// # Examples
+///
+/// ```
+/// use pk_stl::parse_stl;
+/// use pk_stl::indexed_blob::parse_indexed_blob_mesh;
+///
+/// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+/// let (vertices, faces) = parse_indexed_blob_mesh(&model.as_indexed_blob(0.0)).unwrap();
+///
+/// assert_eq!(vertices.len(), 8);
+/// assert_eq!(faces.len(), 12);
+/// ```
+pub fn parse_indexed_blob_mesh(bytes: &[u8]) -> Result<(Vec<Vec3>, Vec<[u32; 3]>)> {
+    let blob = read_blob(bytes)?;
+
+    Ok((blob.vertices, blob.faces.into_iter().map(|(face, _, _)| face).collect()))
+}
+
+/// The contents of an indexed blob.
+struct Blob {
+    header: String,
+    vertices: Vec<Vec3>,
+    faces: Vec<([u32; 3], Vec3, u16)>
+}
+
+fn read_blob(bytes: &[u8]) -> Result<Blob> {
+    let mut reader = ByteCursor::new(bytes);
+
+    if reader.read_bytes(6)? != MAGIC {
+        return Err(Error::binary("Not an indexed blob"));
+    }
+
+    let [version] = reader.read_array()?;
+    if version != VERSION {
+        return Err(Error::binary(&format!("Unsupported indexed blob version {}", version)));
+    }
+
+    let vertex_count = reader.read_u32()? as usize;
+    let triangle_count = reader.read_u32()? as usize;
+    let header_len = reader.read_u32()? as usize;
+    let header = String::from_utf8_lossy(reader.read_bytes(header_len)?).to_string();
+
+    // Check the length up front so the counts can't cause huge allocations.
+    let needed = vertex_count as u64 * BYTES_PER_VERTEX as u64 + triangle_count as u64 * BYTES_PER_TRIANGLE as u64;
+    if (reader.remaining() as u64) < needed {
+        return Err(Error::binary(&format!(
+            "Expected {} bytes of vertices and triangles but only {} remain",
+            needed, reader.remaining()
+        )));
+    }
+
+    let mut vertices = Vec::with_capacity(vertex_count);
+    for _ in 0..vertex_count {
+        vertices.push(reader.read_f32_triplet()?);
+    }
+
+    let mut faces = Vec::with_capacity(triangle_count);
+    for triangle in 0..triangle_count {
+        let face = [reader.read_u32()?, reader.read_u32()?, reader.read_u32()?];
+        if let Some(&index) = face.iter().find(|&&index| index as usize >= vertex_count) {
+            return Err(Error::binary(&format!(
+                "Triangle {} uses vertex {} but there are only {} vertices",
+                triangle, index, vertex_count
+            )));
+        }
+
+        let normal = reader.read_f32_triplet()?;
+        let attribute = reader.read_u16()?;
+
+        faces.push((face, normal, attribute));
+    }
+
+    Ok(Blob { header, vertices, faces })
+}
@@ -0,0 +1,70 @@
+use super::*;
+use crate::parse_stl;
+
+#[test]
+fn test_indexed_blob_round_trip() {
+    let mut model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    model.subdivide(2);
+    model.triangles[3].attribute = 0x1234;
+
+    let blob = model.as_indexed_blob(0.0);
+    let parsed = parse_indexed_blob(&blob).unwrap();
+
+    assert_eq!(parsed.header, model.header);
+    assert_eq!(parsed.triangles, model.triangles);
+    assert_eq!(parsed.source_format(), None);
+
+    let (vertices, faces) = parse_indexed_blob_mesh(&blob).unwrap();
+    assert_eq!((vertices, faces), model.to_indexed(0.0));
+}
+
+#[test]
+fn test_indexed_blob_size() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let header_len = 6 + 1 + 4 + 4 + 4 + model.header.len();
+
+    assert_eq!(model.as_indexed_blob(0.0).len(), header_len + 8 * 12 + 12 * 26);
+}
+
+#[test]
+fn test_indexed_blob_welds_with_epsilon() {
+    let mut model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    model.triangles[0].vertices[0].x += 0.001;
+
+    assert_eq!(parse_indexed_blob_mesh(&model.as_indexed_blob(0.0)).unwrap().0.len(), 9);
+    assert_eq!(parse_indexed_blob_mesh(&model.as_indexed_blob(0.01)).unwrap().0.len(), 8);
+}
+
+#[test]
+fn test_indexed_blob_empty_model() {
+    let empty = StlModel::new("empty", vec![]);
+
+    assert_eq!(parse_indexed_blob(&empty.as_indexed_blob(0.0)).unwrap(), empty);
+}
+
+#[test]
+fn test_parse_invalid_indexed_blob() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let bytes = model.as_indexed_blob(0.0);
+
+    assert!(parse_indexed_blob(b"").is_err());
+    assert!(parse_indexed_blob(include_bytes!("../../tests/test_cube.stl")).is_err());
+    assert!(parse_indexed_blob(&bytes[..bytes.len() - 1]).is_err());
+    assert!(parse_indexed_blob(&model.as_binary_quantized(8)).is_err());
+
+    let mut wrong_version = bytes.clone();
+    wrong_version[6] = 2;
+    assert!(parse_indexed_blob(&wrong_version).is_err());
+
+    // Point the first index of the first triangle past the end of the vertices.
+    let first_index = bytes.len() - 12 * 26;
+    let mut bad_index = bytes.clone();
+    bad_index[first_index..first_index + 4].copy_from_slice(&8u32.to_le_bytes());
+    let error = parse_indexed_blob(&bad_index).unwrap_err();
+    assert!(error.to_string().contains("Triangle 0 uses vertex 8 but there are only 8 vertices"));
+
+    // Huge counts fail without allocating.
+    let mut huge = bytes;
+    huge[7..11].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(parse_indexed_blob(&huge).is_err());
+}
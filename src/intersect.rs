@@ -0,0 +1,195 @@
+use crate::prelude::*;
+
+use crate::StlModel;
+use crate::geometry::{Triangle, Vec3};
+
+#[cfg(test)]
+mod tests;
+
+/// A point or direction with the extra precision of `f64`, used to keep the
+/// intersection tests from being thrown off by rounding.
+type Point = [f64; 3];
+
+impl StlModel {
+    /// Find pairs of triangles that pass through each other.
+    ///
+    /// Returns the indices of each pair of intersecting triangles, with the
+    /// lower index first, sorted. Triangles that share an edge are never
+    /// reported, and triangles that share a single vertex are only reported
+    /// if they cross somewhere other than that vertex. Triangles that only
+    /// touch at a point or along an edge are reported, except for flat
+    /// triangles lying in the same plane, which only count if they overlap.
+    ///
+    /// A model that intersects itself can't be printed or used in boolean
+    /// operations reliably. Only pairs of triangles with overlapping bounding
+    /// boxes are tested, so this is fast for most models.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    ///
+    /// assert!(model.self_intersections().is_empty());
+    /// ```
+    pub fn self_intersections(&self) -> Vec<(usize, usize)> {
+        let bounds: Vec<(Vec3, Vec3)> = self.triangles.iter().map(triangle_bounds).collect();
+
+        // Sweep along the x axis, so only triangles that overlap along it are
+        // compared.
+        let mut order: Vec<usize> = (0..self.triangles.len()).collect();
+        order.sort_by(|&a, &b| bounds[a].0.x.total_cmp(&bounds[b].0.x));
+
+        let mut pairs = Vec::new();
+
+        for (position, &i) in order.iter().enumerate() {
+            let (min, max) = bounds[i];
+
+            for &j in &order[position + 1..] {
+                let (other_min, other_max) = bounds[j];
+
+                if other_min.x > max.x {
+                    break;
+                }
+
+                let overlaps = other_min.y <= max.y && min.y <= other_max.y
+                    && other_min.z <= max.z && min.z <= other_max.z;
+
+                if overlaps && triangles_intersect(&self.triangles[i], &self.triangles[j]) {
+                    pairs.push((i.min(j), i.max(j)));
+                }
+            }
+        }
+
+        pairs.sort_unstable();
+        pairs
+    }
+}
+
+fn triangle_bounds(triangle: &Triangle) -> (Vec3, Vec3) {
+    let [a, b, c] = triangle.vertices;
+
+    (
+        Vec3::new([a.x.min(b.x).min(c.x), a.y.min(b.y).min(c.y), a.z.min(b.z).min(c.z)]),
+        Vec3::new([a.x.max(b.x).max(c.x), a.y.max(b.y).max(c.y), a.z.max(b.z).max(c.z)])
+    )
+}
+
+/// Check whether two triangles intersect, ignoring shared vertices and edges.
+fn triangles_intersect(first: &Triangle, second: &Triangle) -> bool {
+    let shared: Vec<Vec3> = first.vertices.iter()
+        .filter(|v| second.vertices.contains(v))
+        .copied()
+        .collect();
+
+    if shared.len() >= 2 {
+        return false;
+    }
+
+    let a = first.vertices.map(to_point);
+    let b = second.vertices.map(to_point);
+
+    let normal = cross(sub(a[1], a[0]), sub(a[2], a[0]));
+    if b.iter().all(|&p| dot(normal, sub(p, a[0])) == 0.0) {
+        return coplanar_overlap(a, b, normal);
+    }
+
+    // Otherwise they intersect if an edge of one passes through the other.
+    // Edges from a shared vertex always touch the other triangle there, so
+    // they are skipped.
+    let edge_hits = |triangle: [Point; 3], vertices: [Vec3; 3], other: [Point; 3]| {
+        (0..3)
+            .filter(|&i| !shared.contains(&vertices[i]) && !shared.contains(&vertices[(i + 1) % 3]))
+            .any(|i| segment_hits_triangle(triangle[i], triangle[(i + 1) % 3], other))
+    };
+
+    edge_hits(a, first.vertices, b) || edge_hits(b, second.vertices, a)
+}
+
+/// Check whether the segment from `p` to `q` touches the triangle, when the
+/// segment is not in the plane of the triangle.
+fn segment_hits_triangle(p: Point, q: Point, triangle: [Point; 3]) -> bool {
+    let normal = cross(sub(triangle[1], triangle[0]), sub(triangle[2], triangle[0]));
+    let (dp, dq) = (dot(normal, sub(p, triangle[0])), dot(normal, sub(q, triangle[0])));
+
+    if (dp > 0.0 && dq > 0.0) || (dp < 0.0 && dq < 0.0) || dp == dq {
+        return false;
+    }
+
+    // Where the segment crosses the plane of the triangle.
+    let t = dp / (dp - dq);
+    let x = [p[0] + (q[0] - p[0]) * t, p[1] + (q[1] - p[1]) * t, p[2] + (q[2] - p[2]) * t];
+
+    (0..3).all(|i| {
+        let (start, end) = (triangle[i], triangle[(i + 1) % 3]);
+        dot(normal, cross(sub(end, start), sub(x, start))) >= 0.0
+    })
+}
+
+/// Check whether two triangles in the same plane overlap by more than a
+/// shared boundary.
+fn coplanar_overlap(a: [Point; 3], b: [Point; 3], normal: Point) -> bool {
+    // Drop the axis the plane faces most, leaving a 2D problem.
+    let axis = (0..3).max_by(|&i, &j| normal[i].abs().total_cmp(&normal[j].abs())).unwrap_or(2);
+    let flatten = |p: Point| match axis {
+        0 => [p[1], p[2]],
+        1 => [p[2], p[0]],
+        _ => [p[0], p[1]]
+    };
+    let (a, b) = (a.map(flatten), b.map(flatten));
+
+    // Which side of the line from `p` to `q` the point `r` is on.
+    let orientation = |p: [f64; 2], q: [f64; 2], r: [f64; 2]| {
+        let determinant = (q[0] - p[0]) * (r[1] - p[1]) - (q[1] - p[1]) * (r[0] - p[0]);
+
+        if determinant > 0.0 {
+            1
+        } else if determinant < 0.0 {
+            -1
+        } else {
+            0
+        }
+    };
+
+    let edges_cross = (0..3).any(|i| (0..3).any(|j| {
+        let (p, q) = (a[i], a[(i + 1) % 3]);
+        let (r, s) = (b[j], b[(j + 1) % 3]);
+
+        orientation(p, q, r) * orientation(p, q, s) < 0 && orientation(r, s, p) * orientation(r, s, q) < 0
+    }));
+
+    let strictly_inside = |point: [f64; 2], triangle: [[f64; 2]; 3]| {
+        let sides = [
+            orientation(triangle[0], triangle[1], point),
+            orientation(triangle[1], triangle[2], point),
+            orientation(triangle[2], triangle[0], point)
+        ];
+
+        sides.iter().all(|&side| side > 0) || sides.iter().all(|&side| side < 0)
+    };
+
+    edges_cross
+        || a.iter().any(|&p| strictly_inside(p, b))
+        || b.iter().any(|&p| strictly_inside(p, a))
+}
+
+fn to_point(v: Vec3) -> Point {
+    [v.x as f64, v.y as f64, v.z as f64]
+}
+
+fn sub(a: Point, b: Point) -> Point {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: Point, b: Point) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: Point, b: Point) -> Point {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0]
+    ]
+}
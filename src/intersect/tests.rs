@@ -0,0 +1,70 @@
+use crate::{parse_stl, StlModel};
+use crate::geometry::Triangle;
+
+fn triangle(vertices: [[f32; 3]; 3]) -> Triangle {
+    Triangle::from([vertices[0], vertices[1], vertices[2], [0.0; 3]])
+}
+
+#[test]
+fn test_crossing_triangles() {
+    let model = StlModel::new("crossing", vec![
+        triangle([[0.0, 0.0, 0.0], [4.0, 0.0, 0.0], [0.0, 4.0, 0.0]]),
+        // Far away from the others.
+        triangle([[10.0, 10.0, 10.0], [11.0, 10.0, 10.0], [10.0, 11.0, 10.0]]),
+        // Stands up through the middle of the first triangle.
+        triangle([[1.0, 1.0, -1.0], [1.0, 1.0, 1.0], [2.0, 0.5, 0.0]])
+    ]);
+
+    assert_eq!(model.self_intersections(), vec![(0, 2)]);
+}
+
+#[test]
+fn test_triangle_through_edge_of_another() {
+    // The second triangle pokes through the first without any of its edges
+    // crossing it; only an edge of the first crosses the second.
+    let model = StlModel::new("poke", vec![
+        triangle([[-5.0, -5.0, 0.0], [5.0, -5.0, 0.0], [0.0, 5.0, 0.0]]),
+        triangle([[0.0, -10.0, -1.0], [0.0, 10.0, -1.0], [0.0, 0.0, 10.0]])
+    ]);
+
+    assert_eq!(model.self_intersections(), vec![(0, 1)]);
+}
+
+#[test]
+fn test_neighbours_are_not_intersections() {
+    let cube = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    assert!(cube.self_intersections().is_empty());
+
+    let mut subdivided = cube.clone();
+    subdivided.subdivide(2);
+    assert!(subdivided.self_intersections().is_empty());
+}
+
+#[test]
+fn test_shared_vertex_crossing() {
+    let model = StlModel::new("fold", vec![
+        triangle([[0.0, 0.0, 0.0], [4.0, 0.0, 0.0], [0.0, 4.0, 0.0]]),
+        // Shares the corner at the origin, and also passes through the first
+        // triangle away from it.
+        triangle([[0.0, 0.0, 0.0], [2.0, 2.0, -1.0], [1.0, 1.0, 3.0]])
+    ]);
+    assert_eq!(model.self_intersections(), vec![(0, 1)]);
+
+    let touching = StlModel::new("touching", vec![
+        triangle([[0.0, 0.0, 0.0], [4.0, 0.0, 0.0], [0.0, 4.0, 0.0]]),
+        triangle([[0.0, 0.0, 0.0], [-4.0, 0.0, 1.0], [0.0, -4.0, 1.0]])
+    ]);
+    assert!(touching.self_intersections().is_empty());
+}
+
+#[test]
+fn test_overlapping_coplanar_triangles() {
+    let model = StlModel::new("overlap", vec![
+        triangle([[0.0, 0.0, 0.0], [4.0, 0.0, 0.0], [0.0, 4.0, 0.0]]),
+        triangle([[1.0, 1.0, 0.0], [5.0, 1.0, 0.0], [1.0, 5.0, 0.0]]),
+        // Next to the first, sharing only part of an edge.
+        triangle([[0.0, 0.0, 0.0], [0.0, 4.0, 0.0], [-4.0, 0.0, 0.0]])
+    ]);
+
+    assert_eq!(model.self_intersections(), vec![(0, 1)]);
+}
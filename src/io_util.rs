@@ -0,0 +1,111 @@
+//! Helpers for reading little-endian binary data.
+//!
+//! These are the building blocks used to read binary STL files, exposed for
+//! reading formats of your own, such as STL variants with extra data stored
+//! per triangle.
+//!
+//! # Examples
+//!
+//! ```
+//! use pk_stl::io_util::ByteCursor;
+//!
+//! let bytes = [0, 0, 128, 63, 0, 0, 0, 64, 0, 0, 64, 64, 7, 0];
+//! let mut cursor = ByteCursor::new(&bytes);
+//!
+//! assert_eq!(cursor.read_f32_triplet().unwrap(), [1.0, 2.0, 3.0].into());
+//! assert_eq!(cursor.read_u16().unwrap(), 7);
+//! assert_eq!(cursor.remaining(), 0);
+//! assert!(cursor.read_f32().is_err());
+//! ```
+
+use crate::prelude::*;
+
+use crate::error::{Error, Result};
+use crate::geometry::Vec3;
+
+#[cfg(test)]
+mod tests;
+
+/// Reads values from a byte slice in order, keeping track of the position.
+///
+/// Every read either takes the bytes it needs and moves past them, or fails
+/// with an [`ErrorKind::Binary`] error giving the position of the value that
+/// could not be read and leaves the position unchanged.
+///
+/// [`ErrorKind::Binary`]: crate::error::ErrorKind::Binary
+#[derive(Debug, Clone)]
+pub struct ByteCursor<'a> {
+    bytes: &'a [u8],
+    position: usize
+}
+
+impl<'a> ByteCursor<'a> {
+    /// Start reading at the beginning of the bytes.
+    pub fn new(bytes: &'a [u8]) -> ByteCursor<'a> {
+        ByteCursor { bytes, position: 0 }
+    }
+
+    /// How many bytes have been read so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// How many bytes are left to read.
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
+    /// The bytes that have not been read yet.
+    pub fn rest(&self) -> &'a [u8] {
+        &self.bytes[self.position..]
+    }
+
+    /// Read the next `len` bytes.
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        if len > self.remaining() {
+            return Err(Error::binary(&format!(
+                "Unexpected end of data at byte {}: needed {} bytes but only {} remain",
+                self.position, len, self.remaining()
+            )));
+        }
+
+        let read = &self.bytes[self.position..self.position + len];
+        self.position += len;
+
+        Ok(read)
+    }
+
+    /// Read the next `N` bytes as an array.
+    pub fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.read_bytes(N)?);
+
+        Ok(array)
+    }
+
+    /// Read a little-endian `u16`.
+    pub fn read_u16(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.read_array()?))
+    }
+
+    /// Read a little-endian `u32`.
+    pub fn read_u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    /// Read a little-endian `f32`.
+    pub fn read_f32(&mut self) -> Result<f32> {
+        Ok(f32::from_le_bytes(self.read_array()?))
+    }
+
+    /// Read three little-endian `f32`s as the x, y, and z of a vector.
+    ///
+    /// This is how normals and vertices are stored in binary STL files. If
+    /// there are fewer than 12 bytes left, nothing is read.
+    pub fn read_f32_triplet(&mut self) -> Result<Vec3> {
+        let bytes: [u8; 12] = self.read_array()?;
+        let float = |i: usize| f32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+
+        Ok(Vec3::new([float(0), float(4), float(8)]))
+    }
+}
@@ -0,0 +1,56 @@
+use super::*;
+use crate::error::ErrorKind;
+
+fn buffer() -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&1.5f32.to_le_bytes());
+    bytes.extend_from_slice(&(-2.0f32).to_le_bytes());
+    bytes.extend_from_slice(&0.25f32.to_le_bytes());
+    bytes.extend_from_slice(&1e10f32.to_le_bytes());
+    bytes.extend_from_slice(&0xbeefu16.to_le_bytes());
+
+    bytes
+}
+
+#[test]
+fn test_read_floats() {
+    let bytes = buffer();
+    let mut cursor = ByteCursor::new(&bytes);
+
+    assert_eq!(cursor.read_f32_triplet().unwrap(), Vec3::new([1.5, -2.0, 0.25]));
+    assert_eq!(cursor.position(), 12);
+    assert_eq!(cursor.read_f32().unwrap(), 1e10);
+    assert_eq!(cursor.read_u16().unwrap(), 0xbeef);
+    assert_eq!(cursor.remaining(), 0);
+    assert!(cursor.rest().is_empty());
+}
+
+#[test]
+fn test_truncated_read_fails_without_moving() {
+    let bytes = buffer();
+    let mut cursor = ByteCursor::new(&bytes[..10]);
+
+    let err = cursor.read_f32_triplet().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Binary);
+    assert!(err.to_string().contains("at byte 0"));
+    assert_eq!(cursor.position(), 0);
+
+    // The floats that are there can still be read.
+    assert_eq!(cursor.read_f32().unwrap(), 1.5);
+    assert_eq!(cursor.read_f32().unwrap(), -2.0);
+
+    let err = cursor.read_f32().unwrap_err();
+    assert!(err.to_string().contains("at byte 8"));
+    assert_eq!(cursor.remaining(), 2);
+    assert_eq!(cursor.read_u16().unwrap(), u16::from_le_bytes([bytes[8], bytes[9]]));
+}
+
+#[test]
+fn test_read_bytes() {
+    let mut cursor = ByteCursor::new(b"PKSTL");
+
+    assert_eq!(cursor.read_bytes(2).unwrap(), b"PK");
+    assert_eq!(cursor.read_array::<3>().unwrap(), *b"STL");
+    assert!(cursor.read_bytes(1).is_err());
+    assert_eq!(cursor.read_bytes(0).unwrap(), b"");
+}
@@ -4,6 +4,33 @@
 //! is written entirely in Rust with no dependencies, and it can read and write
 //! both ASCII and binary STL files.
 //!
+//! # Features
+//!
+//! The crate can be used without the standard library. The `std` feature is
+//! enabled by default; disable it and enable `libm` instead to build for
+//! `no_std` targets that have an allocator. Functions that work with files are
+//! only available with `std`.
+//!
+//! - `std` (default): Use the standard library.
+//! - `libm`: Use the `libm` crate for floating point math when `std` is
+//!   disabled.
+//!
+//! Optional integrations with other crates can be enabled with the following
+//! features. `glam` and `nalgebra` work without `std` too, using `libm` for
+//! their own math.
+//!
+//! - `glam`: Conversions between [`geometry::Vec3`] and `glam::Vec3`, and
+//!   between [`geometry::Triangle`] and `[glam::Vec3; 4]`.
+//! - `nalgebra`: Conversions between [`geometry::Vec3`] and
+//!   `nalgebra::Vector3<f32>`, and `StlModel::apply_isometry` for moving a
+//!   model with an `nalgebra::Isometry3<f32>`.
+//! - `mmap`: `parse_stl_mmap` for parsing large files by memory-mapping them
+//!   instead of reading them into memory first.
+//! - `units`: A `Unit` stored on each model, and
+//!   `StlModel::convert_units` for scaling a model from one unit to another.
+//! - `gzip`: `parse_stl_gz` for parsing gzip-compressed (`.stl.gz`) files,
+//!   using the `flate2` crate.
+//!
 //! # Examples
 //!
 //! ```
@@ -16,58 +43,328 @@
 //! // Models can be converted between ascii and binary.
 //! let ascii_content = model.as_ascii();
 //!
-//! // The header of this model is "OpenSCAD Model\n" because this file happens
-//! // to be the output of OpenSCAD.
+//! // The header of this model is "OpenSCAD Model" because this file happens to
+//! // be the output of OpenSCAD.
 //! assert_eq!(ascii_content.lines().next(), Some("solid OpenSCAD Model"));
 //! ```
 
-use std::fmt::Write;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("pk_stl requires either the `std` or the `libm` feature to be enabled");
+
+extern crate alloc;
+
+use core::fmt;
+use core::str::FromStr;
+use alloc::borrow::Cow;
 
 pub mod geometry;
 pub mod error;
+pub mod quantized;
+pub mod indexed_blob;
+pub mod io_util;
 
 mod binary;
 mod ascii;
+mod prelude;
+#[cfg(not(any(feature = "std", test)))]
+mod math;
+mod index;
+mod obj;
+mod ply;
+mod measure;
+mod repair;
+mod slice;
+#[cfg(feature = "std")]
+mod file;
+mod topology;
+mod transform;
+mod hull;
+mod obb;
+mod query;
+mod bvh;
+mod remesh;
+mod intersect;
+mod validate;
+mod diff;
+mod primitives;
+mod hash;
+
+#[cfg(feature = "glam")]
+mod glam_interop;
+#[cfg(feature = "nalgebra")]
+mod nalgebra_interop;
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(feature = "units")]
+mod units;
+#[cfg(feature = "gzip")]
+mod gzip;
 
 #[cfg(test)]
 mod tests;
 
 use geometry::Triangle;
-use error::Result;
+use error::{Error, Result};
+use prelude::*;
+
+pub use ascii::{tokenize, Token};
+pub use binary::{parse_binary_into, parse_binary_stl_endian, parse_binary_stl_with_progress, Endianness};
+#[cfg(feature = "std")]
+pub use file::parse_stl_file;
+#[cfg(feature = "std")]
+pub use ascii::read_ascii_stl;
+pub use validate::ValidationReport;
+pub use diff::ModelDiff;
+pub use bvh::TriangleBvh;
+
+#[cfg(feature = "mmap")]
+pub use mmap::parse_stl_mmap;
+#[cfg(feature = "units")]
+pub use units::Unit;
+#[cfg(feature = "gzip")]
+pub use gzip::parse_stl_gz;
+
+/// The two formats of STL files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StlFormat {
+    /// The human-readable text format, starting with `solid`.
+    Ascii,
+    /// The compact binary format, with an 80 byte header.
+    Binary
+}
 
 /// The main structure of this crate. It represents a single STL model.
 ///
 /// STL files are composed of a header and a list of triangles. This structure
 /// represents both of those things.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Models that are parsed also remember which format they were parsed from
+/// (see [`StlModel::source_format`]). Models built in code should be created
+/// with [`StlModel::new`].
+#[derive(Debug, Clone)]
 pub struct StlModel {
     /// The main header line of the STL file.
     ///
     /// Some STL files do use the header to convey information about the model,
     /// but this is not required. The header is not used by this crate.
+    ///
+    /// Binary files pad the header to 80 bytes, so trailing nulls and
+    /// whitespace are removed when parsing them.
     pub header: String,
     /// Each triangle in the model.
-    pub triangles: Vec<Triangle>
+    pub triangles: Vec<Triangle>,
+    /// The format the model was parsed from, if it was parsed.
+    source_format: Option<StlFormat>,
+    /// The unit of the coordinates.
+    #[cfg(feature = "units")]
+    unit: Unit
+}
+
+/// Two models are equal if they have the same header and triangles, and the
+/// same unit when the `units` feature is enabled. The format they were parsed
+/// from is not compared.
+impl PartialEq for StlModel {
+    fn eq(&self, other: &StlModel) -> bool {
+        #[cfg(feature = "units")]
+        if self.unit != other.unit {
+            return false;
+        }
+
+        self.header == other.header && self.triangles == other.triangles
+    }
 }
 
 impl StlModel {
+    /// Create a model from a header and a list of triangles.
+    pub fn new(header: impl Into<String>, triangles: Vec<Triangle>) -> StlModel {
+        StlModel {
+            header: header.into(),
+            triangles,
+            source_format: None,
+            #[cfg(feature = "units")]
+            unit: Unit::default()
+        }
+    }
+
+    /// Create an empty model with room for `cap` triangles.
+    ///
+    /// This avoids reallocating the list of triangles while adding triangles
+    /// one at a time, when the number of triangles is known in advance.
+    pub fn with_capacity(header: impl Into<String>, cap: usize) -> StlModel {
+        StlModel::new(header, Vec::with_capacity(cap))
+    }
+
+    /// The format the model was parsed from.
+    ///
+    /// This is `None` for models that were not parsed from an STL file, such
+    /// as models created with [`StlModel::new`] or [`StlModel::from_obj`].
+    ///
+    /// This records where the model came from, not what it contains, so it
+    /// stays the same when the model is edited, including when every
+    /// triangle is removed or replaced. Writing the model in another format
+    /// doesn't change it either.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::{parse_stl, StlFormat, StlModel};
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// assert_eq!(model.source_format(), Some(StlFormat::Binary));
+    ///
+    /// let model = StlModel::new("empty", vec![]);
+    /// assert_eq!(model.source_format(), None);
+    /// ```
+    pub fn source_format(&self) -> Option<StlFormat> {
+        self.source_format
+    }
+
+    /// The triangles of the model, for editing in place.
+    ///
+    /// Triangles can be changed but not added or removed; use
+    /// [`StlModel::extend_triangles`] and [`StlModel::retain`] for that.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let mut model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    ///
+    /// for triangle in model.triangles_mut() {
+    ///     triangle.attribute = 1;
+    /// }
+    ///
+    /// assert!(model.triangles.iter().all(|triangle| triangle.attribute == 1));
+    /// ```
+    pub fn triangles_mut(&mut self) -> &mut [Triangle] {
+        &mut self.triangles
+    }
+
+    /// Call `f` on each triangle in order, allowing it to be changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let mut model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    ///
+    /// model.for_each_triangle_mut(|triangle| triangle.normal = triangle.calculate_normal());
+    /// ```
+    pub fn for_each_triangle_mut(&mut self, f: impl FnMut(&mut Triangle)) {
+        self.triangles.iter_mut().for_each(f);
+    }
+
+    /// Keep only the triangles for which `f` returns true.
+    ///
+    /// The remaining triangles keep their order. This is the same as calling
+    /// `retain` on the list of triangles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let mut model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    ///
+    /// // Drop everything touching the bottom of the cube.
+    /// model.retain(|triangle| triangle.vertices.iter().all(|v| v.z > 0.0));
+    ///
+    /// assert_eq!(model.triangles.len(), 2);
+    /// ```
+    pub fn retain(&mut self, f: impl FnMut(&Triangle) -> bool) {
+        self.triangles.retain(f);
+    }
+
+    /// Remove every triangle, keeping the header.
+    ///
+    /// The memory used for the triangles is kept, so the model can be filled
+    /// again without reallocating.
+    pub fn clear(&mut self) {
+        self.triangles.clear();
+    }
+
+    /// Add a triangle to the end of the model, returning its index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::StlModel;
+    /// use pk_stl::geometry::Triangle;
+    ///
+    /// let mut model = StlModel::new("empty", vec![]);
+    /// let index = model.push_triangle(Triangle::from([[0.0; 3]; 4]));
+    ///
+    /// assert_eq!(index, 0);
+    /// assert_eq!(model.triangles.len(), 1);
+    /// ```
+    pub fn push_triangle(&mut self, triangle: Triangle) -> usize {
+        self.triangles.push(triangle);
+
+        self.triangles.len() - 1
+    }
+
+    /// Add triangles to the end of the model.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::{parse_stl, StlModel};
+    ///
+    /// let cube = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// let mut model = StlModel::new("two cubes", vec![]);
+    ///
+    /// model.extend_triangles(cube.triangles.iter().copied());
+    /// model.extend_triangles(cube.triangles);
+    ///
+    /// assert_eq!(model.triangles.len(), 24);
+    /// ```
+    pub fn extend_triangles(&mut self, iter: impl IntoIterator<Item = Triangle>) {
+        self.triangles.extend(iter);
+    }
+
     /// Convert the model to ASCII STL format.
     ///
     /// This will use the header of the model, trimmed with newlines removed.
+    ///
+    /// Numbers are written in scientific notation and always include a
+    /// decimal point, for example `1.0e1` for ten. Parsing the result gives
+    /// back exactly the same coordinates.
     pub fn as_ascii(&self) -> String {
         let mut result = String::new();
 
-        writeln!(result, "solid {}", self.header.trim().replace("\n", " ")).unwrap();
+        ascii::write_ascii_stl(&mut result, &self.header_line(), &self.triangles).unwrap();
 
-        for triangle in &self.triangles {
-            writeln!(result, "facet normal {:e} {:e} {:e}", triangle.normal.x, triangle.normal.y, triangle.normal.z).unwrap();
-            writeln!(result, "    outer loop").unwrap();
-            for v in &triangle.vertices {
-                writeln!(result, "        vertex {:e} {:e} {:e}", v.x, v.y, v.z).unwrap();
-            }
-            writeln!(result, "    endloop").unwrap();
-            writeln!(result, "endfacet").unwrap();
-        }
+        result
+    }
+
+    /// Convert the model to ASCII STL format, choosing how the header is
+    /// written.
+    ///
+    /// This is the same as [`StlModel::as_ascii`], except that `header_fn` is
+    /// given the header of the model and returns the text to write after
+    /// `solid`. The result should not contain newlines, or the file will not
+    /// parse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::StlModel;
+    ///
+    /// let model = StlModel::new("Crème brûlée", vec![]);
+    ///
+    /// // Keep only ASCII characters.
+    /// let ascii = model.as_ascii_with(|header| header.chars().filter(char::is_ascii).collect());
+    ///
+    /// assert_eq!(ascii, "solid Crme brle\n");
+    /// ```
+    pub fn as_ascii_with<F: Fn(&str) -> String>(&self, header_fn: F) -> String {
+        let mut result = String::new();
+
+        ascii::write_ascii_stl(&mut result, &header_fn(&self.header), &self.triangles).unwrap();
 
         result
     }
@@ -75,13 +372,24 @@ impl StlModel {
     /// Convert the model to binary STL format.
     ///
     /// This will use the header of the model, trimmed with newlines removed.
+    /// The binary header is always 80 bytes: shorter headers are padded with
+    /// null bytes, and longer ones are cut off at the last whole character
+    /// that fits.
+    ///
+    /// The attribute bytes of each triangle are taken from
+    /// [`Triangle::attribute`], so values read from a binary file (such as
+    /// colours) are written back unchanged. Triangles parsed from ASCII or
+    /// created without an attribute have zero there.
     pub fn as_binary(&self) -> Vec<u8> {
-        let mut result = Vec::new();
+        let mut result = Vec::with_capacity(84 + self.triangles.len() * 50);
 
-        // Write the header
-        let header = self.header.trim().replace("\n", " ");
-        let header = header.as_bytes();
-        result.extend_from_slice(header);
+        // Write the header, without splitting a multi-byte character
+        let header = self.header_line();
+        let length = (0..=header.len().min(80))
+            .rev()
+            .find(|&i| header.is_char_boundary(i))
+            .unwrap_or(0);
+        result.extend_from_slice(&header.as_bytes()[..length]);
         result.resize(80, 0);
 
         // Write the number of triangles
@@ -90,22 +398,26 @@ impl StlModel {
 
         // Write each triangle
         for triangle in &self.triangles {
-            result.extend_from_slice(&triangle.normal.x.to_le_bytes());
-            result.extend_from_slice(&triangle.normal.y.to_le_bytes());
-            result.extend_from_slice(&triangle.normal.z.to_le_bytes());
-
-            for v in &triangle.vertices {
-                result.extend_from_slice(&v.x.to_le_bytes());
-                result.extend_from_slice(&v.y.to_le_bytes());
-                result.extend_from_slice(&v.z.to_le_bytes());
-            }
-
-            result.extend_from_slice(&[0u8; 2]);
+            result.extend_from_slice(&triangle.to_le_bytes());
         }
 
         result
     }
 
+    /// The header as a single line, for writing to a file.
+    ///
+    /// The header is trimmed and any newlines are replaced with spaces. It is
+    /// only copied if it has newlines to replace.
+    pub(crate) fn header_line(&self) -> Cow<'_, str> {
+        let header = self.header.trim();
+
+        if header.contains('\n') {
+            Cow::Owned(header.replace('\n', " "))
+        } else {
+            Cow::Borrowed(header)
+        }
+    }
+
     /// Find the range of positions in the model.
     ///
     /// This will return and optional tuple of three ranges. The values is only
@@ -123,6 +435,14 @@ impl StlModel {
     /// | z          | 4.0     | 5.0     |
     ///
     /// This is useful for determining the size of the model.
+    ///
+    /// A model whose triangles are flat in some direction, or all collapsed
+    /// to a single point, still gives `Some`, with the minimum and maximum
+    /// equal along those axes. Use [`StlModel::bounding_box`] and
+    /// [`BoundingBox::is_degenerate`] to check for this.
+    ///
+    /// [`BoundingBox::is_degenerate`]: geometry::BoundingBox::is_degenerate
+    #[allow(clippy::type_complexity)]
     pub fn dimension_range(&self) -> Option<((f32, f32), (f32, f32), (f32, f32))> {
         let mut maybe_range: Option<((f32, f32), (f32, f32), (f32, f32))> = None;
 
@@ -147,16 +467,325 @@ impl StlModel {
     }
 }
 
+/// Write the model in ASCII STL format.
+///
+/// This produces the same text as [`StlModel::as_ascii`], but writes it
+/// directly to the formatter instead of building a string first.
+///
+/// # Examples
+///
+/// ```
+/// use pk_stl::StlModel;
+///
+/// let model = StlModel::new("empty", vec![]);
+///
+/// assert_eq!(model.to_string(), model.as_ascii());
+/// ```
+impl fmt::Display for StlModel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        ascii::write_ascii_stl(f, &self.header_line(), &self.triangles)
+    }
+}
+
+/// Parse an ASCII STL model from a string.
+///
+/// Binary STL files are not valid strings, so this only accepts the ASCII
+/// format. Use [`parse_stl`] for data that may be in either format.
+///
+/// # Examples
+///
+/// ```
+/// use pk_stl::StlModel;
+///
+/// let model: StlModel = "solid empty\nendsolid empty".parse().unwrap();
+///
+/// assert_eq!(model.header, "empty");
+/// assert!(model.triangles.is_empty());
+/// ```
+impl FromStr for StlModel {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<StlModel> {
+        ascii::parse_ascii_stl(s.as_bytes())
+    }
+}
+
+/// Parse an STL file from bytes, in either format.
+///
+/// This is the same as [`parse_stl`].
+///
+/// # Examples
+///
+/// ```
+/// use pk_stl::StlModel;
+///
+/// let content: &[u8] = include_bytes!("../tests/test_cube.stl");
+/// let model = StlModel::try_from(content).unwrap();
+///
+/// assert_eq!(model.triangles.len(), 12);
+/// ```
+impl TryFrom<&[u8]> for StlModel {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<StlModel> {
+        parse_stl(bytes)
+    }
+}
+
+/// Create a model with an empty header from a list of triangles.
+///
+/// This is the same as [`StlModel::new`] with an empty header.
+///
+/// # Examples
+///
+/// ```
+/// use pk_stl::StlModel;
+/// use pk_stl::geometry::Triangle;
+///
+/// let model = StlModel::from(vec![Triangle::from([[0.0; 3]; 4])]);
+///
+/// assert_eq!(model.header, "");
+/// assert_eq!(model.triangles.len(), 1);
+/// ```
+impl From<Vec<Triangle>> for StlModel {
+    fn from(triangles: Vec<Triangle>) -> StlModel {
+        StlModel::new(String::new(), triangles)
+    }
+}
+
+/// Options controlling how STL files are parsed.
+///
+/// The default options accept the small deviations from the format that some
+/// programs make. [`ParseOptions::strict`] rejects them instead.
+///
+/// # Examples
+///
+/// ```
+/// use pk_stl::{parse_stl_with_options, ParseOptions};
+///
+/// let options = ParseOptions {
+///     reject_unknown_keywords: true,
+///     ..ParseOptions::default()
+/// };
+///
+/// assert!(parse_stl_with_options(b"solid a\ncolor 1 0 0\nendsolid a", options).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ParseOptions {
+    /// Fail on lines in ASCII files that are not part of the STL format.
+    ///
+    /// Some exporters add extra lines, such as `color` lines inside facets.
+    /// When this is false those lines are skipped.
+    pub reject_unknown_keywords: bool,
+    /// Fail if the length of a binary file is not exactly what the triangle
+    /// count after the header says it should be.
+    ///
+    /// This catches trailing garbage after the last triangle, which often
+    /// means the file is corrupt or its triangle count is off by one. When
+    /// this is false, extra data after the last triangle is ignored and a
+    /// last triangle missing its attribute bytes is accepted.
+    pub require_matching_size: bool,
+    /// Fail if an ASCII file does not end with an `endsolid` line.
+    ///
+    /// When this is false, a file that stops after its last facet is
+    /// accepted.
+    pub require_endsolid: bool,
+    /// Leave the header of the model empty instead of reading it from the
+    /// file.
+    ///
+    /// This saves converting and storing the header when it isn't needed.
+    /// See [`parse_stl_headerless`].
+    pub skip_header: bool,
+    /// What to do with the attribute field of each triangle in binary files.
+    ///
+    /// ASCII files have no attribute field, so this has no effect on them.
+    pub attributes: AttributePolicy
+}
+
+/// What to do with the attribute field of triangles when parsing binary
+/// files, as set in [`ParseOptions::attributes`].
+///
+/// # Examples
+///
+/// ```
+/// use pk_stl::{parse_stl_with_options, AttributePolicy, ParseOptions, StlModel};
+/// use pk_stl::geometry::Triangle;
+///
+/// let mut colored = Triangle::from([[0.0; 3]; 4]);
+/// colored.set_color((255, 0, 0));
+/// let mut other = Triangle::from([[0.0; 3]; 4]);
+/// other.attribute = 7;
+///
+/// let bytes = StlModel::new("", vec![colored, other]).as_binary();
+/// let options = ParseOptions { attributes: AttributePolicy::ValidColorsOnly, ..ParseOptions::default() };
+/// let model = parse_stl_with_options(&bytes, options).unwrap();
+///
+/// assert_eq!(model.triangles[0].color(), Some((255, 0, 0)));
+/// assert_eq!(model.triangles[1].attribute, 0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AttributePolicy {
+    /// Set every attribute to zero.
+    Ignore,
+    /// Keep every attribute exactly as it is stored.
+    #[default]
+    Preserve,
+    /// Keep only valid colour attributes (RGB555 plus a valid bit), and set
+    /// the rest to zero.
+    ///
+    /// This uses the convention of [`Triangle::color`]: five bits for each
+    /// channel and a bit marking the colour as valid, as used by VisCAM and
+    /// SolidView. Attributes without the valid bit are usually zero or used
+    /// for something else, so they are cleared rather than being mistaken
+    /// for colours. The attributes that are kept are not changed; read them
+    /// with [`Triangle::color`]. Other layouts, such as RGB565, are not
+    /// recognised.
+    ///
+    /// [`Triangle::color`]: geometry::Triangle::color
+    ValidColorsOnly
+}
+
+impl ParseOptions {
+    /// Options that reject anything that does not follow the format exactly.
+    pub fn strict() -> ParseOptions {
+        ParseOptions {
+            reject_unknown_keywords: true,
+            require_matching_size: true,
+            require_endsolid: true,
+            skip_header: false,
+            attributes: AttributePolicy::Preserve
+        }
+    }
+}
+
 /// Parse an STL file from bytes.
 ///
-/// The bytes can be either ASCII or binary. Whether the file is ASCII or binary
-/// will be determined by the first 6 bytes of the file. If the file starts
-/// with "solid ", it will be parsed as ASCII. Otherwise, it will be parsed as
-/// binary.
+/// The bytes can be either ASCII or binary. The format is determined with
+/// [`detect_format`]. If the data is not valid binary and its size doesn't
+/// match the binary layout either, it is also tried as ASCII. When both fail,
+/// the error has the kind [`ErrorKind::Format`] and describes both failures.
+///
+/// [`ErrorKind::Format`]: error::ErrorKind::Format
+///
+/// This uses the default [`ParseOptions`], which accept some common
+/// deviations from the format. Use [`parse_stl_with_options`] to change them.
 pub fn parse_stl(bytes: &[u8]) -> Result<StlModel> {
-    if &bytes[0..6] == b"solid " {
-        ascii::parse_ascii_stl(bytes)
+    parse_stl_with_options(bytes, ParseOptions::default())
+}
+
+/// Parse an STL file from bytes, leaving the header of the model empty.
+///
+/// This is the same as [`parse_stl`], except that the header is skipped
+/// rather than copied into the model. When parsing many files and only the
+/// triangles are needed, this saves an allocation for each file.
+///
+/// # Examples
+///
+/// ```
+/// use pk_stl::parse_stl_headerless;
+///
+/// let model = parse_stl_headerless(include_bytes!("../tests/test_cube.stl")).unwrap();
+///
+/// assert_eq!(model.header, "");
+/// assert_eq!(model.triangles.len(), 12);
+/// ```
+pub fn parse_stl_headerless(bytes: &[u8]) -> Result<StlModel> {
+    parse_stl_with_options(bytes, ParseOptions { skip_header: true, ..ParseOptions::default() })
+}
+
+/// Parse an STL file from bytes with the given options.
+///
+/// This is the same as [`parse_stl`], except that the options control how
+/// strictly the data must follow the format.
+pub fn parse_stl_with_options(bytes: &[u8], options: ParseOptions) -> Result<StlModel> {
+    match detect_format(bytes) {
+        Some(StlFormat::Ascii) => ascii::parse_ascii_stl_with_options(bytes, &options),
+        Some(StlFormat::Binary) | None => binary::parse_binary_stl_with_options(bytes, &options)
+            .or_else(|binary_error| {
+                // Data with the right size for binary is almost certainly
+                // meant to be binary, so the binary error is the useful one.
+                if binary::matches_binary_size(bytes) {
+                    return Err(binary_error);
+                }
+
+                ascii::parse_ascii_stl_with_options(bytes, &options).map_err(|ascii_error| {
+                    Error::format(&format!(
+                        "The data is neither a binary nor an ASCII STL file ({}; {})",
+                        binary_error, ascii_error
+                    ))
+                })
+            })
+    }
+}
+
+/// The typical size of one facet in an ASCII STL file, in bytes.
+///
+/// Exporters that write numbers with six decimal places use about 250 bytes
+/// for each facet. This is lower so that estimates err on the high side.
+const ASCII_BYTES_PER_FACET: usize = 200;
+
+/// Estimate how many triangles STL data holds, without parsing it.
+///
+/// For binary data this is the triangle count stored after the header, which
+/// is exact for a valid file. ASCII files don't store a count, so it is
+/// estimated from the length of the data, assuming about 200 bytes for each
+/// facet. Files written with short numbers can have several times more
+/// triangles than the estimate.
+///
+/// Returns `None` if the data is too short to be either format. This can be
+/// used to reject files that would be too large before parsing them, or to
+/// reserve space for the triangles.
+///
+/// # Examples
+///
+/// ```
+/// use pk_stl::estimated_triangle_count;
+///
+/// let binary = include_bytes!("../tests/test_cube.stl");
+///
+/// assert_eq!(estimated_triangle_count(binary), Some(12));
+/// ```
+pub fn estimated_triangle_count(bytes: &[u8]) -> Option<usize> {
+    match detect_format(bytes)? {
+        StlFormat::Ascii => Some(bytes.len() / ASCII_BYTES_PER_FACET),
+        StlFormat::Binary => {
+            let count = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]);
+
+            Some(count as usize)
+        }
+    }
+}
+
+/// Determine whether STL data is in the ASCII or binary format.
+///
+/// This does not check that the data is valid, only which format it claims to
+/// be. If the data starts with "solid " it is usually ASCII. Otherwise, it is
+/// binary if it is long enough to hold the 80 byte header and triangle count.
+/// Returns `None` if the data is too short to be either.
+///
+/// The two formats are ambiguous: some programs write binary files whose
+/// header starts with "solid ", which makes them look like ASCII files. Data
+/// that starts with "solid " is treated as binary if its length is exactly
+/// what a binary file with the triangle count stored after the header would
+/// be. An ASCII file is very unlikely to match that length by chance.
+///
+/// # Examples
+///
+/// ```
+/// use pk_stl::{detect_format, StlFormat};
+///
+/// let binary = include_bytes!("../tests/test_cube.stl");
+/// assert_eq!(detect_format(binary), Some(StlFormat::Binary));
+///
+/// assert_eq!(detect_format(b"solid cube\nendsolid cube"), Some(StlFormat::Ascii));
+/// assert_eq!(detect_format(b"solid"), None);
+/// ```
+pub fn detect_format(bytes: &[u8]) -> Option<StlFormat> {
+    if bytes.starts_with(b"solid ") && !binary::matches_binary_size(bytes) {
+        Some(StlFormat::Ascii)
+    } else if bytes.len() >= 84 {
+        Some(StlFormat::Binary)
     } else {
-        binary::parse_binary_stl(bytes)
+        None
     }
 }
@@ -0,0 +1,66 @@
+//! Floating point functions that are provided by `std` but not `core`.
+//!
+//! Without `std` these are implemented using `libm`. The methods have the same
+//! names as the inherent methods in `std`, so code using them does not need to
+//! change depending on the feature.
+
+pub trait FloatExt {
+    fn sqrt(self) -> Self;
+    fn floor(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn round(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+}
+
+impl FloatExt for f32 {
+    fn sqrt(self) -> f32 {
+        libm::sqrtf(self)
+    }
+
+    fn floor(self) -> f32 {
+        libm::floorf(self)
+    }
+
+    fn sin(self) -> f32 {
+        libm::sinf(self)
+    }
+
+    fn cos(self) -> f32 {
+        libm::cosf(self)
+    }
+
+    fn round(self) -> f32 {
+        libm::roundf(self)
+    }
+
+    fn atan2(self, other: f32) -> f32 {
+        libm::atan2f(self, other)
+    }
+}
+
+impl FloatExt for f64 {
+    fn sqrt(self) -> f64 {
+        libm::sqrt(self)
+    }
+
+    fn floor(self) -> f64 {
+        libm::floor(self)
+    }
+
+    fn sin(self) -> f64 {
+        libm::sin(self)
+    }
+
+    fn cos(self) -> f64 {
+        libm::cos(self)
+    }
+
+    fn round(self) -> f64 {
+        libm::round(self)
+    }
+
+    fn atan2(self, other: f64) -> f64 {
+        libm::atan2(self, other)
+    }
+}
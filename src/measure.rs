@@ -0,0 +1,312 @@
+use crate::prelude::*;
+
+use crate::StlModel;
+use crate::geometry::{BoundingBox, Triangle, Vec3};
+use crate::topology::edge_map;
+
+#[cfg(test)]
+mod tests;
+
+impl StlModel {
+    /// The total area of all the triangles in the model.
+    pub fn surface_area(&self) -> f32 {
+        self.triangles.iter().map(|triangle| triangle.area()).sum()
+    }
+
+    /// The volume enclosed by the model.
+    ///
+    /// This is only meaningful for closed models whose triangles all face
+    /// outwards (see [`StlModel::fix_winding`]). If they all face inwards the
+    /// result is negative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    ///
+    /// assert_eq!(model.volume(), 1000.0);
+    /// ```
+    pub fn volume(&self) -> f32 {
+        // Sum the signed volumes of the tetrahedrons formed by each triangle
+        // and the origin. The parts outside the model cancel out.
+        let sum: f32 = self.triangles.iter()
+            .map(|triangle| {
+                let [a, b, c] = triangle.vertices;
+                a.dot(b.cross(c))
+            })
+            .sum();
+
+        sum / 6.0
+    }
+
+    /// The smallest axis-aligned box that contains every vertex of the model.
+    ///
+    /// Returns `None` if the model has no triangles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// let bounds = model.bounding_box().unwrap();
+    ///
+    /// assert_eq!(bounds.size(), [10.0, 10.0, 10.0].into());
+    /// ```
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        let mut vertices = self.triangles.iter().flat_map(|triangle| triangle.vertices);
+        let first = vertices.next()?;
+
+        Some(vertices.fold(BoundingBox { min: first, max: first }, |bounds, v| BoundingBox {
+            min: Vec3::new([bounds.min.x.min(v.x), bounds.min.y.min(v.y), bounds.min.z.min(v.z)]),
+            max: Vec3::new([bounds.max.x.max(v.x), bounds.max.y.max(v.y), bounds.max.z.max(v.z)])
+        }))
+    }
+
+    /// How much of its bounding box the model fills.
+    ///
+    /// This is the [volume](StlModel::volume) of the model divided by the
+    /// volume of its [bounding box](StlModel::bounding_box): 1.0 for a solid
+    /// box, and closer to zero for hollow or spindly parts. The model volume
+    /// is taken as positive, so a model facing inwards gives the same ratio.
+    ///
+    /// Returns `None` if the model has no triangles or is flat along any
+    /// axis.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    ///
+    /// assert_eq!(model.fill_ratio(), Some(1.0));
+    /// ```
+    pub fn fill_ratio(&self) -> Option<f32> {
+        let box_volume = self.bounding_box()?.volume();
+
+        if box_volume <= 0.0 {
+            return None;
+        }
+
+        Some(self.volume().abs() / box_volume)
+    }
+
+    /// The length of the shortest edge of any triangle.
+    ///
+    /// This is a rough measure of the smallest feature in the model, which
+    /// can be compared with the nozzle size or resolution of a printer.
+    /// Edges with no length belong to degenerate triangles and are skipped,
+    /// as are edges with non-finite vertices. Returns `None` if there are no
+    /// other edges.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    ///
+    /// assert_eq!(model.shortest_edge(), Some(10.0));
+    /// ```
+    pub fn shortest_edge(&self) -> Option<f32> {
+        self.edge_lengths()
+            .filter(|&length| length > 0.0)
+            .reduce(f32::min)
+    }
+
+    /// The length of the longest edge of any triangle.
+    ///
+    /// Edges with non-finite vertices are skipped. Returns `None` if there
+    /// are no other edges.
+    pub fn longest_edge(&self) -> Option<f32> {
+        self.edge_lengths().reduce(f32::max)
+    }
+
+    /// The total length of the edges of the triangles, as if the model were
+    /// drawn as a wireframe.
+    ///
+    /// Without `dedup`, every edge of every triangle is counted, so an edge
+    /// shared by two triangles is counted twice. With `dedup`, each edge
+    /// between two distinct vertex positions is counted once, however many
+    /// triangles use it. Edges with non-finite vertices are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    ///
+    /// // Twelve edges of 10, and a diagonal of about 14.14 across each face.
+    /// assert!((model.total_edge_length(true) - 204.85).abs() < 0.01);
+    /// assert!((model.total_edge_length(false) - 409.71).abs() < 0.01);
+    /// ```
+    pub fn total_edge_length(&self, dedup: bool) -> f32 {
+        if !dedup {
+            return self.edge_lengths().sum();
+        }
+
+        let (vertices, faces) = self.to_indexed(0.0);
+
+        edge_map(&faces).keys()
+            .map(|&(a, b)| vertices[a as usize].distance(vertices[b as usize]))
+            .filter(|length| length.is_finite())
+            .sum()
+    }
+
+    /// The finite lengths of the three edges of every triangle.
+    fn edge_lengths(&self) -> impl Iterator<Item = f32> + '_ {
+        self.triangles.iter()
+            .flat_map(|triangle| {
+                let [a, b, c] = triangle.vertices;
+                [a.distance(b), b.distance(c), c.distance(a)]
+            })
+            .filter(|length| length.is_finite())
+    }
+
+    /// The area of the downward-facing surface that overhangs by more than
+    /// the given angle.
+    ///
+    /// The overhang angle of a triangle is how far it leans past vertical: 0
+    /// degrees for a vertical wall and 90 degrees for a flat, downward-facing
+    /// ceiling. Triangles facing upwards never overhang. When 3D printing,
+    /// surfaces that overhang by more than about 45 degrees usually need
+    /// support.
+    ///
+    /// `up` is the upwards direction (usually positive z) and does not need to
+    /// be unit length. The orientation of each triangle is calculated from its
+    /// vertices with [`Triangle::calculate_normal`] rather than taken from the
+    /// stored normal.
+    ///
+    /// [`Triangle::calculate_normal`]: crate::geometry::Triangle::calculate_normal
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    ///
+    /// // Only the 10x10 bottom of the cube overhangs.
+    /// assert_eq!(model.overhang_area(45.0, [0.0, 0.0, 1.0].into()), 100.0);
+    /// ```
+    pub fn overhang_area(&self, max_angle_degrees: f32, up: Vec3) -> f32 {
+        let up = up.normalize();
+
+        // A triangle overhangs by more than the angle when its normal is more
+        // than that angle below horizontal.
+        let threshold = -max_angle_degrees.to_radians().sin();
+
+        self.triangles.iter()
+            .filter(|triangle| triangle.calculate_normal().dot(up) < threshold)
+            .map(|triangle| triangle.area())
+            .sum()
+    }
+
+    /// Count how many triangles face in each direction.
+    ///
+    /// The sphere of directions is divided into `buckets` roughly equal
+    /// regions, each around an evenly spaced direction. Each triangle is
+    /// counted in the region its normal points into. Returns the direction of
+    /// each region that any triangle faces and the number of triangles in it,
+    /// from the most triangles to the fewest.
+    ///
+    /// The direction of each triangle is calculated from its vertices with
+    /// [`Triangle::calculate_normal`] rather than taken from the stored
+    /// normal, and triangles with no area are left out. The directions that
+    /// most of the model faces show which flat sides it could rest on when 3D
+    /// printing. See [`StlModel::normal_area_histogram`] to weight each
+    /// triangle by its area instead.
+    ///
+    /// [`Triangle::calculate_normal`]: crate::geometry::Triangle::calculate_normal
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// let histogram = model.normal_histogram(100);
+    ///
+    /// // Two triangles on each of the six faces.
+    /// assert_eq!(histogram.len(), 6);
+    /// assert!(histogram.iter().all(|&(_, count)| count == 2));
+    /// ```
+    pub fn normal_histogram(&self, buckets: usize) -> Vec<(Vec3, usize)> {
+        let directions = sphere_directions(buckets);
+        let mut counts = vec![0; buckets];
+
+        for triangle in &self.triangles {
+            if let Some(bucket) = normal_bucket(triangle, &directions) {
+                counts[bucket] += 1;
+            }
+        }
+
+        sorted_histogram(counts, &directions, |&count| count > 0)
+    }
+
+    /// Total the area of the triangles facing in each direction.
+    ///
+    /// This is the same as [`StlModel::normal_histogram`], except that each
+    /// region gives the total area of its triangles rather than how many there
+    /// are. This is less affected by how finely each surface is divided into
+    /// triangles.
+    pub fn normal_area_histogram(&self, buckets: usize) -> Vec<(Vec3, f32)> {
+        let directions = sphere_directions(buckets);
+        let mut areas = vec![0.0; buckets];
+
+        for triangle in &self.triangles {
+            if let Some(bucket) = normal_bucket(triangle, &directions) {
+                areas[bucket] += triangle.area();
+            }
+        }
+
+        sorted_histogram(areas, &directions, |&area| area > 0.0)
+    }
+}
+
+/// The index of the direction that is closest to the normal of the
+/// triangle.
+fn normal_bucket(triangle: &Triangle, directions: &[Vec3]) -> Option<usize> {
+    let normal = triangle.calculate_normal();
+
+    if normal.length() == 0.0 {
+        return None;
+    }
+
+    (0..directions.len()).max_by(|&a, &b| directions[a].dot(normal).total_cmp(&directions[b].dot(normal)))
+}
+
+/// `count` directions spread evenly over the sphere, using a Fibonacci
+/// spiral from the top to the bottom.
+fn sphere_directions(count: usize) -> Vec<Vec3> {
+    // The golden angle, in radians.
+    const GOLDEN_ANGLE: f32 = 2.399_963_2;
+
+    (0..count)
+        .map(|index| {
+            let z = 1.0 - (2 * index + 1) as f32 / count as f32;
+            let radius = (1.0 - z * z).max(0.0).sqrt();
+            let angle = GOLDEN_ANGLE * index as f32;
+
+            Vec3::new([radius * angle.cos(), radius * angle.sin(), z])
+        })
+        .collect()
+}
+
+/// Pair each bucket with its direction, keeping the non-empty ones, with the
+/// largest first.
+fn sorted_histogram<T: PartialOrd + Copy>(values: Vec<T>, directions: &[Vec3], keep: impl Fn(&T) -> bool) -> Vec<(Vec3, T)> {
+    let mut histogram: Vec<(Vec3, T)> = directions.iter()
+        .copied()
+        .zip(values)
+        .filter(|(_, value)| keep(value))
+        .collect();
+
+    // A stable sort keeps buckets with equal values in spiral order.
+    histogram.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(core::cmp::Ordering::Equal));
+    histogram
+}
@@ -0,0 +1,220 @@
+use crate::{parse_stl, StlModel};
+use crate::geometry::{Triangle, Vec3};
+
+#[test]
+fn test_cube_surface_area() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    assert_eq!(model.surface_area(), 600.0);
+}
+
+#[test]
+fn test_cube_edge_lengths() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    assert_eq!(model.shortest_edge(), Some(10.0));
+
+    // The diagonals across each face.
+    let longest = model.longest_edge().unwrap();
+    assert!((longest - 10.0 * 2.0f32.sqrt()).abs() < 1e-4);
+}
+
+#[test]
+fn test_cube_total_edge_length() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let diagonal = 10.0 * 2.0f32.sqrt();
+
+    // Each of the 12 triangles has two sides and a diagonal.
+    assert!((model.total_edge_length(false) - 12.0 * (20.0 + diagonal)).abs() < 1e-3);
+
+    // The 12 edges of the cube and the 6 face diagonals, each counted once.
+    assert!((model.total_edge_length(true) - (120.0 + 6.0 * diagonal)).abs() < 1e-3);
+
+    assert_eq!(StlModel::new(String::new(), vec![]).total_edge_length(true), 0.0);
+}
+
+#[test]
+fn test_edge_lengths_skip_degenerate_edges() {
+    let model = StlModel::new(String::new(), vec![
+        Triangle::from([
+            [0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0],
+            [3.0, 4.0, 0.0],
+            [0.0, 0.0, 0.0]
+        ])
+    ]);
+
+    assert_eq!(model.shortest_edge(), Some(5.0));
+    assert_eq!(model.longest_edge(), Some(5.0));
+
+    let empty = StlModel::new(String::new(), vec![]);
+    assert_eq!(empty.shortest_edge(), None);
+    assert_eq!(empty.longest_edge(), None);
+}
+
+#[test]
+fn test_overhang_area() {
+    // Both triangles face downwards. The normal of the first is 30 degrees
+    // from straight down (overhanging by 60 degrees), and the normal of the
+    // second is 60 degrees from straight down (overhanging by 30 degrees).
+    let steep = 30.0f32.to_radians().tan();
+    let shallow = 60.0f32.to_radians().tan();
+
+    let model = StlModel::new(String::new(), vec![
+        Triangle::from([
+            [0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [1.0, 0.0, steep],
+            [0.0, 0.0, 0.0]
+        ]),
+        Triangle::from([
+            [0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [1.0, 0.0, shallow],
+            [0.0, 0.0, 0.0]
+        ])
+    ]);
+    let up = [0.0, 0.0, 1.0].into();
+
+    let first_area = model.triangles[0].area();
+    let second_area = model.triangles[1].area();
+
+    assert!((model.overhang_area(45.0, up) - first_area).abs() < 1e-6);
+    assert!((model.overhang_area(20.0, up) - (first_area + second_area)).abs() < 1e-6);
+    assert_eq!(model.overhang_area(80.0, up), 0.0);
+
+    // Upside down, nothing overhangs.
+    assert_eq!(model.overhang_area(0.0, [0.0, 0.0, -1.0].into()), 0.0);
+}
+
+/// A tetrahedron with three edges of length one along the axes, with all
+/// triangles facing outwards.
+fn corner_tetrahedron() -> StlModel {
+    let o = [0.0, 0.0, 0.0];
+    let x = [1.0, 0.0, 0.0];
+    let y = [0.0, 1.0, 0.0];
+    let z = [0.0, 0.0, 1.0];
+
+    let mut model = StlModel::new("tetrahedron", vec![
+        Triangle::from([o, y, x, o]),
+        Triangle::from([o, x, z, o]),
+        Triangle::from([o, z, y, o]),
+        Triangle::from([x, y, z, o])
+    ]);
+    model.recalculate_normals();
+
+    model
+}
+
+#[test]
+fn test_volume() {
+    let cube = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    assert_eq!(cube.volume(), 1000.0);
+
+    let mut tetrahedron = corner_tetrahedron();
+    assert!((tetrahedron.volume() - 1.0 / 6.0).abs() < 1e-6);
+
+    for triangle in &mut tetrahedron.triangles {
+        triangle.flip();
+    }
+    assert!((tetrahedron.volume() + 1.0 / 6.0).abs() < 1e-6);
+
+    assert_eq!(StlModel::new("empty", vec![]).volume(), 0.0);
+}
+
+#[test]
+fn test_bounding_box() {
+    let cube = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let bounds = cube.bounding_box().unwrap();
+
+    assert_eq!(bounds.size(), [10.0, 10.0, 10.0].into());
+    assert_eq!(bounds.volume(), 1000.0);
+
+    let bounds = corner_tetrahedron().bounding_box().unwrap();
+    assert_eq!(bounds.min, [0.0, 0.0, 0.0].into());
+    assert_eq!(bounds.max, [1.0, 1.0, 1.0].into());
+
+    assert_eq!(StlModel::new("empty", vec![]).bounding_box(), None);
+}
+
+#[test]
+fn test_single_point_model_is_degenerate() {
+    let point = [2.0, 3.0, 4.0];
+    let model = StlModel::new("point", vec![Triangle::from([point, point, point, [0.0; 3]])]);
+
+    assert_eq!(model.dimension_range(), Some(((2.0, 2.0), (3.0, 3.0), (4.0, 4.0))));
+
+    let bounds = model.bounding_box().unwrap();
+    assert!(bounds.is_degenerate());
+    assert_eq!(bounds.volume(), 0.0);
+
+    assert!(!corner_tetrahedron().bounding_box().unwrap().is_degenerate());
+}
+
+#[test]
+fn test_fill_ratio() {
+    let cube = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    assert!((cube.fill_ratio().unwrap() - 1.0).abs() < 1e-6);
+
+    let ratio = corner_tetrahedron().fill_ratio().unwrap();
+    assert!((ratio - 1.0 / 6.0).abs() < 1e-6);
+
+    assert_eq!(StlModel::new("empty", vec![]).fill_ratio(), None);
+
+    // A single flat triangle has a bounding box with no volume.
+    let flat = StlModel::new("flat", vec![Triangle::from([
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 1.0]
+    ])]);
+    assert_eq!(flat.fill_ratio(), None);
+}
+
+#[test]
+fn test_cube_normal_histogram() {
+    let cube = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let histogram = cube.normal_histogram(200);
+
+    // Six dominant directions, one for each face, with two triangles each.
+    assert_eq!(histogram.len(), 6);
+    assert_eq!(histogram.iter().map(|&(_, count)| count).sum::<usize>(), 12);
+
+    let axes = [
+        [1.0, 0.0, 0.0], [-1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0], [0.0, -1.0, 0.0],
+        [0.0, 0.0, 1.0], [0.0, 0.0, -1.0]
+    ];
+    for axis in axes {
+        let axis = axis.into();
+        let (direction, count) = histogram.iter()
+            .copied()
+            .max_by(|a, b| a.0.dot(axis).total_cmp(&b.0.dot(axis)))
+            .unwrap();
+
+        assert_eq!(count, 2);
+        assert!(direction.dot(axis) > 0.95);
+        assert!((direction.length() - 1.0).abs() < 1e-5);
+    }
+
+    let areas = cube.normal_area_histogram(200);
+    assert_eq!(areas.len(), 6);
+    assert!(areas.iter().all(|&(_, area)| area == 100.0));
+}
+
+#[test]
+fn test_normal_histogram_sorts_largest_first() {
+    let mut model = corner_tetrahedron();
+    // Split the slanted face into more triangles so it has the most.
+    model.subdivide(1);
+    model.triangles.extend(corner_tetrahedron().triangles[..3].iter().copied());
+
+    let histogram = model.normal_histogram(50);
+    let counts: Vec<usize> = histogram.iter().map(|&(_, count)| count).collect();
+
+    assert_eq!(counts, vec![5, 5, 5, 4]);
+    assert!(histogram[3].0.dot(Vec3::new([1.0, 1.0, 1.0]).normalize()) > 0.9);
+
+    assert!(model.normal_histogram(0).is_empty());
+    assert!(StlModel::new("empty", vec![]).normal_histogram(10).is_empty());
+}
@@ -0,0 +1,32 @@
+use std::fs::File;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::{parse_stl, StlModel};
+use crate::error::Result;
+
+#[cfg(test)]
+mod tests;
+
+/// Parse an STL file by memory-mapping it.
+///
+/// This avoids reading the whole file into memory before parsing, which is
+/// useful for very large binary files. The format is detected the same way as
+/// in [`parse_stl`].
+///
+/// The file must not be modified by another process while it is being parsed.
+/// The contents of a memory-mapped file can change underneath the parser, in
+/// which case the resulting model is unspecified.
+///
+/// This function is only available with the `mmap` feature.
+pub fn parse_stl_mmap<P: AsRef<Path>>(path: P) -> Result<StlModel> {
+    let file = File::open(path)?;
+
+    // SAFETY: The map is only read from while parsing, and is dropped before
+    // returning. Concurrent modification of the file is documented above as
+    // the caller's responsibility.
+    let map = unsafe { Mmap::map(&file)? };
+
+    parse_stl(&map)
+}
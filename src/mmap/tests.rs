@@ -0,0 +1,22 @@
+use super::*;
+
+#[test]
+fn test_parse_stl_mmap() {
+    let content = include_bytes!("../../tests/test_cube.stl");
+    let path = std::env::temp_dir().join(format!("pk_stl_mmap_test_{}.stl", std::process::id()));
+    std::fs::write(&path, content).unwrap();
+
+    let result = parse_stl_mmap(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(result.unwrap(), parse_stl(content).unwrap());
+}
+
+#[test]
+fn test_parse_stl_mmap_missing_file() {
+    let path = std::env::temp_dir().join("pk_stl_mmap_test_does_not_exist.stl");
+
+    let err = parse_stl_mmap(path).unwrap_err();
+
+    assert_eq!(err.kind(), crate::error::ErrorKind::Io);
+}
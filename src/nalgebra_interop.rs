@@ -0,0 +1,44 @@
+use nalgebra::{Isometry3, Point3, Vector3};
+
+use crate::StlModel;
+use crate::geometry::Vec3;
+
+#[cfg(test)]
+mod tests;
+
+impl From<Vector3<f32>> for Vec3 {
+    fn from(other: Vector3<f32>) -> Vec3 {
+        Vec3 {
+            x: other.x,
+            y: other.y,
+            z: other.z
+        }
+    }
+}
+
+impl From<Vec3> for Vector3<f32> {
+    fn from(other: Vec3) -> Vector3<f32> {
+        Vector3::new(other.x, other.y, other.z)
+    }
+}
+
+impl StlModel {
+    /// Apply a rigid transformation (rotation and translation) to the model.
+    ///
+    /// Every vertex is transformed as a point, so it is both rotated and
+    /// translated. Normals are only rotated, since translating a direction has
+    /// no meaning. Isometries preserve lengths and angles, so the normals stay
+    /// unit length and the triangles keep their orientation.
+    ///
+    /// This method is only available with the `nalgebra` feature.
+    pub fn apply_isometry(&mut self, isometry: &Isometry3<f32>) {
+        for triangle in &mut self.triangles {
+            triangle.normal = isometry.transform_vector(&triangle.normal.into()).into();
+
+            for vertex in &mut triangle.vertices {
+                let point = Point3::from(Vector3::from(*vertex));
+                *vertex = isometry.transform_point(&point).coords.into();
+            }
+        }
+    }
+}
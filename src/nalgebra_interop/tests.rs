@@ -0,0 +1,39 @@
+use std::f32::consts::FRAC_PI_2;
+
+use super::*;
+use crate::geometry::Triangle;
+
+#[test]
+fn test_vec3_round_trip() {
+    let original = Vec3::new([1.0, -2.0, 3.5]);
+
+    let converted: Vector3<f32> = original.into();
+    assert_eq!(converted, Vector3::new(1.0, -2.0, 3.5));
+
+    let back: Vec3 = converted.into();
+    assert_eq!(back, original);
+}
+
+#[test]
+fn test_apply_isometry() {
+    let mut model = StlModel::new(String::new(), vec![
+        Triangle::from([
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0]
+        ])
+    ]);
+
+    // Rotate a quarter turn around the y axis, then move up by 5.
+    let isometry = Isometry3::new(Vector3::new(0.0, 0.0, 5.0), Vector3::y() * FRAC_PI_2);
+    model.apply_isometry(&isometry);
+
+    let triangle = model.triangles[0];
+    let close = |a: Vec3, b: [f32; 3]| (a - b.into()).length() < 1e-5;
+
+    assert!(close(triangle.vertices[0], [0.0, 0.0, 5.0]));
+    assert!(close(triangle.vertices[1], [0.0, 0.0, 4.0]));
+    assert!(close(triangle.vertices[2], [0.0, 1.0, 5.0]));
+    assert!(close(triangle.normal, [1.0, 0.0, 0.0]));
+}
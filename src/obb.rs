@@ -0,0 +1,170 @@
+use crate::prelude::*;
+
+use crate::StlModel;
+use crate::geometry::Vec3;
+
+#[cfg(test)]
+mod tests;
+
+/// The most Jacobi rotations to use when finding the principal axes. Three by
+/// three matrices converge in far fewer.
+const MAX_ROTATIONS: usize = 50;
+
+impl StlModel {
+    /// The smallest box around the model found with its axes in any
+    /// direction, rather than lined up with x, y, and z.
+    ///
+    /// Returns the centre of the box, its three axes, and half its length
+    /// along each axis, or `None` if the model has no triangles. The axes are
+    /// unit length, perpendicular to each other, and ordered from the longest
+    /// side of the box to the shortest.
+    ///
+    /// The axes are first found by principal component analysis of the
+    /// vertices, which lines them up with the directions the model is most
+    /// and least spread out in. Shapes that are spread out evenly, such as a
+    /// cube, have no such directions, so boxes lined up with each face of the
+    /// [convex hull](StlModel::convex_hull) are also tried, and the one with
+    /// the least volume is returned. This is a close fit, but not always the
+    /// smallest possible box.
+    ///
+    /// This fits rotated parts much more tightly than
+    /// [`StlModel::bounding_box`], which is useful for packing parts or
+    /// choosing how to orient them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// let (center, _, half_extents) = model.oriented_bounding_box().unwrap();
+    ///
+    /// assert!((center - [5.0, 5.0, 5.0].into()).length() < 1e-4);
+    /// assert!((half_extents - [5.0, 5.0, 5.0].into()).length() < 1e-4);
+    /// ```
+    pub fn oriented_bounding_box(&self) -> Option<(Vec3, [Vec3; 3], Vec3)> {
+        let (vertices, _) = self.to_indexed(0.0);
+        if vertices.is_empty() {
+            return None;
+        }
+
+        // Only the corners of the hull can touch the sides of the box, so the
+        // candidates only need to be checked against those.
+        let hull = self.convex_hull();
+        let points: Vec<Vec3> = if hull.triangles.is_empty() {
+            vertices.clone()
+        } else {
+            hull.to_indexed(0.0).0
+        };
+
+        let mut best = fit_box(&points, principal_axes(&vertices));
+
+        for triangle in &hull.triangles {
+            let normal = triangle.calculate_normal();
+
+            for i in 0..3 {
+                let edge = (triangle.vertices[(i + 1) % 3] - triangle.vertices[i]).normalize();
+                if edge.length() == 0.0 {
+                    continue;
+                }
+
+                let candidate = fit_box(&points, [edge, normal.cross(edge).normalize(), normal]);
+                if volume(&candidate) < volume(&best) {
+                    best = candidate;
+                }
+            }
+        }
+
+        Some(best)
+    }
+}
+
+/// The box around the points with the given axes, with the axes sorted from
+/// the longest side to the shortest.
+fn fit_box(points: &[Vec3], axes: [Vec3; 3]) -> (Vec3, [Vec3; 3], Vec3) {
+    let mut sides = axes.map(|axis| {
+        let (min, max) = points.iter()
+            .map(|point| point.dot(axis))
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), d| (min.min(d), max.max(d)));
+
+        (axis, (min + max) / 2.0, (max - min) / 2.0)
+    });
+    sides.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+    let center = sides.iter().fold(Vec3::new([0.0; 3]), |center, &(axis, middle, _)| center + axis * middle);
+
+    (
+        center,
+        [sides[0].0, sides[1].0, sides[0].0.cross(sides[1].0)],
+        Vec3::new([sides[0].2, sides[1].2, sides[2].2])
+    )
+}
+
+fn volume(obb: &(Vec3, [Vec3; 3], Vec3)) -> f32 {
+    let half_extents = obb.2;
+
+    half_extents.x * half_extents.y * half_extents.z
+}
+
+/// The directions the points are most spread out in, found from the
+/// eigenvectors of their covariance matrix.
+fn principal_axes(points: &[Vec3]) -> [Vec3; 3] {
+    let count = points.len() as f32;
+    let mean = points.iter().fold(Vec3::new([0.0; 3]), |sum, &point| sum + point) * (1.0 / count);
+
+    let mut covariance = [[0.0f32; 3]; 3];
+    for &point in points {
+        let offset = point - mean;
+
+        for (i, row) in covariance.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value += offset[i] * offset[j] / count;
+            }
+        }
+    }
+
+    let (values, vectors) = symmetric_eigen(covariance);
+
+    let mut order = [0, 1, 2];
+    order.sort_by(|&a, &b| values[b].total_cmp(&values[a]));
+    let axis = |i: usize| Vec3::new([vectors[0][i], vectors[1][i], vectors[2][i]]);
+
+    let (first, second) = (axis(order[0]), axis(order[1]));
+    [first, second, first.cross(second)]
+}
+
+/// The eigenvalues and eigenvectors of a symmetric matrix, using the Jacobi
+/// eigenvalue algorithm.
+///
+/// The eigenvectors are the columns of the returned matrix.
+fn symmetric_eigen(mut matrix: [[f32; 3]; 3]) -> ([f32; 3], [[f32; 3]; 3]) {
+    let mut vectors = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+    for _ in 0..MAX_ROTATIONS {
+        // Rotate away the largest value off the diagonal.
+        let (p, q) = [(0, 1), (0, 2), (1, 2)].into_iter()
+            .max_by(|&(a, b), &(c, d)| matrix[a][b].abs().total_cmp(&matrix[c][d].abs()))
+            .unwrap_or((0, 1));
+
+        let scale = matrix[0][0].abs() + matrix[1][1].abs() + matrix[2][2].abs();
+        if matrix[p][q].abs() <= scale * 1e-9 {
+            break;
+        }
+
+        let theta = (matrix[q][q] - matrix[p][p]) / (2.0 * matrix[p][q]);
+        let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+        let c = 1.0 / (t * t + 1.0).sqrt();
+        let s = t * c;
+
+        for row in matrix.iter_mut().chain(vectors.iter_mut()) {
+            let (kp, kq) = (row[p], row[q]);
+            row[p] = c * kp - s * kq;
+            row[q] = s * kp + c * kq;
+        }
+        let (row_p, row_q) = (matrix[p], matrix[q]);
+        matrix[p] = core::array::from_fn(|k| c * row_p[k] - s * row_q[k]);
+        matrix[q] = core::array::from_fn(|k| s * row_p[k] + c * row_q[k]);
+    }
+
+    ([matrix[0][0], matrix[1][1], matrix[2][2]], vectors)
+}
@@ -0,0 +1,96 @@
+use crate::{parse_stl, StlModel};
+use crate::geometry::Vec3;
+
+/// Rotate a point around an axis through the origin, using Rodrigues'
+/// rotation formula.
+fn rotate(point: Vec3, axis: Vec3, angle: f32) -> Vec3 {
+    let axis = axis.normalize();
+    let (sin, cos) = angle.sin_cos();
+
+    point * cos + axis.cross(point) * sin + axis * (axis.dot(point) * (1.0 - cos))
+}
+
+fn rotated(model: &StlModel, axis: Vec3, angle: f32) -> StlModel {
+    let mut model = model.clone();
+
+    for triangle in &mut model.triangles {
+        for vertex in &mut triangle.vertices {
+            *vertex = rotate(*vertex, axis, angle);
+        }
+    }
+    model.recalculate_normals();
+
+    model
+}
+
+fn assert_close(a: Vec3, b: Vec3) {
+    assert!((a - b).length() < 1e-3, "{:?} is not close to {:?}", a, b);
+}
+
+#[test]
+fn test_rotated_cube_fits_better_than_axis_aligned_box() {
+    let cube = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let axis = Vec3::new([1.0, 2.0, 3.0]);
+    let model = rotated(&cube, axis, 0.7);
+
+    let (center, axes, half_extents) = model.oriented_bounding_box().unwrap();
+
+    assert_close(half_extents, [5.0, 5.0, 5.0].into());
+    assert_close(center, rotate([5.0, 5.0, 5.0].into(), axis, 0.7));
+
+    let obb_volume = 8.0 * half_extents.x * half_extents.y * half_extents.z;
+    let aabb_volume = model.bounding_box().unwrap().volume();
+    assert!((obb_volume - 1000.0).abs() < 1.0);
+    assert!(aabb_volume > 1500.0);
+
+    // The axes line up with the rotated faces of the cube.
+    for axis in axes {
+        assert!((axis.length() - 1.0).abs() < 1e-5);
+
+        let aligned = model.triangles.iter().any(|triangle| triangle.normal.dot(axis).abs() > 0.9999);
+        assert!(aligned);
+    }
+}
+
+#[test]
+fn test_rotated_box_axes_follow_its_shape() {
+    // A 10 by 4 by 2 box, which has distinct principal axes.
+    let mut block = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    for triangle in &mut block.triangles {
+        for vertex in &mut triangle.vertices {
+            *vertex = *vertex * Vec3::new([1.0, 0.4, 0.2]);
+        }
+    }
+
+    let axis = Vec3::new([-2.0, 1.0, 0.5]);
+    let model = rotated(&block, axis, 1.2);
+
+    let (center, axes, half_extents) = model.oriented_bounding_box().unwrap();
+
+    assert_close(half_extents, [5.0, 2.0, 1.0].into());
+    assert_close(center, rotate([5.0, 2.0, 1.0].into(), axis, 1.2));
+
+    let expected = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    for (axis_found, direction) in axes.iter().zip(expected) {
+        let direction = rotate(direction.into(), axis, 1.2);
+        assert!(axis_found.dot(direction).abs() > 0.9999);
+    }
+
+    // The axes are right-handed.
+    assert!(axes[0].cross(axes[1]).dot(axes[2]) > 0.9999);
+}
+
+#[test]
+fn test_flat_and_empty_models() {
+    assert_eq!(StlModel::new("empty", vec![]).oriented_bounding_box(), None);
+
+    let flat = StlModel::new("flat", vec![crate::geometry::Triangle::from([
+        [0.0, 0.0, 0.0],
+        [4.0, 0.0, 0.0],
+        [0.0, 2.0, 0.0],
+        [0.0, 0.0, 1.0]
+    ])]);
+    let (_, _, half_extents) = flat.oriented_bounding_box().unwrap();
+
+    assert!(half_extents.z.abs() < 1e-5);
+}
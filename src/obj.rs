@@ -0,0 +1,174 @@
+use core::fmt::Write;
+
+use crate::StlModel;
+use crate::prelude::*;
+use crate::error::{Error, Result};
+use crate::geometry::{Triangle, Vec3};
+
+#[cfg(test)]
+mod tests;
+
+impl StlModel {
+    /// Convert the model to Wavefront OBJ format.
+    ///
+    /// Duplicate vertices are welded together (see [`StlModel::to_indexed`]),
+    /// so the output contains one `v` line per unique vertex and one `f` line
+    /// per triangle. Face indices are 1-based, as required by the OBJ format.
+    ///
+    /// The header of the model is written as a comment on the first line.
+    pub fn to_obj(&self) -> String {
+        self.write_obj(false)
+    }
+
+    /// Convert the model to Wavefront OBJ format, including normals.
+    ///
+    /// This is the same as [`StlModel::to_obj`], but additionally writes one
+    /// `vn` line for the normal of each triangle and references it from the
+    /// face using the `f v//vn` syntax.
+    pub fn to_obj_with_normals(&self) -> String {
+        self.write_obj(true)
+    }
+
+    /// Create a model from a Wavefront OBJ file.
+    ///
+    /// Only the minimal subset of OBJ needed to describe a triangle mesh is
+    /// supported: `v` lines for vertices and `f` lines for faces. All other
+    /// lines (normals, texture coordinates, groups, materials, etc.) are
+    /// ignored. Faces must be triangles; polygons with more than three
+    /// vertices are rejected rather than triangulated. Negative (relative)
+    /// indices are supported.
+    ///
+    /// OBJ files have no header, so the header for the model must be given.
+    /// The normals of the triangles are calculated from the vertices using
+    /// [`Triangle::calculate_normal`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::StlModel;
+    ///
+    /// let obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+    /// let model = StlModel::from_obj(obj, "triangle".to_string()).unwrap();
+    ///
+    /// assert_eq!(model.triangles.len(), 1);
+    /// assert_eq!(model.triangles[0].normal, [0.0, 0.0, 1.0].into());
+    /// ```
+    pub fn from_obj(text: &str, header: String) -> Result<StlModel> {
+        let mut vertices: Vec<Vec3> = Vec::new();
+        let mut triangles = Vec::new();
+
+        for (line_index, line) in text.lines().enumerate() {
+            let line_number = line_index + 1;
+            let line = match line.find('#') {
+                Some(comment_start) => &line[..comment_start],
+                None => line
+            };
+
+            let mut parts = line.split_whitespace();
+
+            match parts.next() {
+                Some("v") => {
+                    let mut coordinates = [0.0; 3];
+
+                    for value in &mut coordinates {
+                        *value = parts.next()
+                            .and_then(|part| part.parse::<f32>().ok())
+                            .ok_or_else(|| Error::obj(&format!(
+                                "Line {}: expected three vertex coordinates", line_number
+                            )))?;
+                    }
+
+                    vertices.push(coordinates.into());
+                },
+                Some("f") => {
+                    let references: Vec<&str> = parts.collect();
+
+                    if references.len() != 3 {
+                        return Err(Error::obj(&format!(
+                            "Line {}: only triangular faces are supported, found a face with {} vertices",
+                            line_number,
+                            references.len()
+                        )));
+                    }
+
+                    let mut face = [Vec3::new([0.0; 3]); 3];
+                    for (vertex, reference) in face.iter_mut().zip(references) {
+                        *vertex = resolve_vertex(&vertices, reference, line_number)?;
+                    }
+
+                    let mut triangle = Triangle {
+                        normal: Vec3::new([0.0; 3]),
+                        vertices: face,
+                        attribute: 0
+                    };
+                    triangle.normal = triangle.calculate_normal();
+
+                    triangles.push(triangle);
+                },
+                _ => {}
+            }
+        }
+
+        Ok(StlModel::new(header, triangles))
+    }
+
+    fn write_obj(&self, include_normals: bool) -> String {
+        let mut result = String::new();
+        let (vertices, faces) = self.to_indexed(0.0);
+
+        let header = self.header_line();
+        if !header.is_empty() {
+            writeln!(result, "# {}", header).unwrap();
+        }
+
+        for v in &vertices {
+            writeln!(result, "v {} {} {}", v.x, v.y, v.z).unwrap();
+        }
+
+        if include_normals {
+            for triangle in &self.triangles {
+                let n = triangle.normal;
+                writeln!(result, "vn {} {} {}", n.x, n.y, n.z).unwrap();
+            }
+        }
+
+        for (i, face) in faces.iter().enumerate() {
+            let [a, b, c] = face.map(|index| index + 1);
+
+            if include_normals {
+                let n = i + 1;
+                writeln!(result, "f {}//{} {}//{} {}//{}", a, n, b, n, c, n).unwrap();
+            } else {
+                writeln!(result, "f {} {} {}", a, b, c).unwrap();
+            }
+        }
+
+        result
+    }
+}
+
+
+/// Look up the vertex referred to by a face entry such as `3`, `3/1`, `3//2`
+/// or `-1`.
+fn resolve_vertex(vertices: &[Vec3], reference: &str, line_number: usize) -> Result<Vec3> {
+    let index = reference.split('/').next().unwrap_or_default();
+    let index: i64 = index.parse().map_err(|_| {
+        Error::obj(&format!("Line {}: invalid vertex index {:?}", line_number, reference))
+    })?;
+
+    // Positive indices are 1-based, and negative indices count back from the
+    // most recently defined vertex.
+    let position = if index > 0 {
+        index - 1
+    } else {
+        vertices.len() as i64 + index
+    };
+
+    if index == 0 || position < 0 || position >= vertices.len() as i64 {
+        return Err(Error::obj(&format!(
+            "Line {}: face references missing vertex {}", line_number, index
+        )));
+    }
+
+    Ok(vertices[position as usize])
+}
\ No newline at end of file
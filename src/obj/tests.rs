@@ -0,0 +1,83 @@
+use crate::{parse_stl, StlModel};
+use crate::error::ErrorKind;
+
+fn count_lines(obj: &str, prefix: &str) -> usize {
+    obj.lines().filter(|line| line.starts_with(prefix)).count()
+}
+
+#[test]
+fn test_cube_to_obj() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    let obj = model.to_obj();
+
+    assert_eq!(obj.lines().next(), Some("# OpenSCAD Model"));
+    assert_eq!(count_lines(&obj, "v "), 8);
+    assert_eq!(count_lines(&obj, "vn "), 0);
+    assert_eq!(count_lines(&obj, "f "), 12);
+
+    // Every index must be 1-based and refer to an existing vertex.
+    for line in obj.lines().filter(|line| line.starts_with("f ")) {
+        for index in line.split_whitespace().skip(1) {
+            let index: usize = index.parse().unwrap();
+            assert!((1..=8).contains(&index));
+        }
+    }
+}
+
+#[test]
+fn test_cube_to_obj_with_normals() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    let obj = model.to_obj_with_normals();
+
+    assert_eq!(count_lines(&obj, "v "), 8);
+    assert_eq!(count_lines(&obj, "vn "), 12);
+    assert_eq!(count_lines(&obj, "f "), 12);
+    assert!(obj.contains("f 1//1 "));
+}
+
+#[test]
+fn test_from_obj_round_trip() {
+    let src = "# a unit square
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+f 1 2 3
+f 1/1 3/3 -1
+";
+
+    let model = StlModel::from_obj(src, "square".to_string()).unwrap();
+
+    assert_eq!(model.header, "square");
+    assert_eq!(model.triangles.len(), 2);
+    for triangle in &model.triangles {
+        assert_eq!(triangle.normal, [0.0, 0.0, 1.0].into());
+    }
+
+    let obj = model.to_obj();
+    assert_eq!(count_lines(&obj, "v "), 4);
+    assert_eq!(count_lines(&obj, "f "), 2);
+
+    let reparsed = StlModel::from_obj(&obj, "square".to_string()).unwrap();
+    assert_eq!(reparsed, model);
+}
+
+#[test]
+fn test_from_obj_rejects_polygons() {
+    let src = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n";
+
+    let err = StlModel::from_obj(src, String::new()).unwrap_err();
+
+    assert_eq!(err.kind(), ErrorKind::Obj);
+    assert!(err.to_string().contains("Line 5"));
+}
+
+#[test]
+fn test_from_obj_rejects_missing_vertices() {
+    let src = "v 0 0 0\nv 1 0 0\nv 1 1 0\nf 1 2 4\n";
+
+    assert!(StlModel::from_obj(src, String::new()).is_err());
+    assert!(StlModel::from_obj("v 0 0 0\nf 0 1 1\n", String::new()).is_err());
+}
@@ -0,0 +1,46 @@
+use core::fmt::Write;
+
+use crate::StlModel;
+use crate::prelude::*;
+
+#[cfg(test)]
+mod tests;
+
+impl StlModel {
+    /// Convert the model to ASCII PLY format.
+    ///
+    /// Duplicate vertices are welded together (see [`StlModel::to_indexed`]),
+    /// so the output has one vertex element per unique vertex and one face
+    /// element per triangle, with faces referring to vertices by their 0-based
+    /// index. The header of the model is written as a `comment` line.
+    pub fn to_ply_ascii(&self) -> String {
+        let mut result = String::new();
+        let (vertices, faces) = self.to_indexed(0.0);
+
+        writeln!(result, "ply").unwrap();
+        writeln!(result, "format ascii 1.0").unwrap();
+
+        let header = self.header_line();
+        if !header.is_empty() {
+            writeln!(result, "comment {}", header).unwrap();
+        }
+
+        writeln!(result, "element vertex {}", vertices.len()).unwrap();
+        writeln!(result, "property float x").unwrap();
+        writeln!(result, "property float y").unwrap();
+        writeln!(result, "property float z").unwrap();
+        writeln!(result, "element face {}", faces.len()).unwrap();
+        writeln!(result, "property list uchar uint vertex_indices").unwrap();
+        writeln!(result, "end_header").unwrap();
+
+        for v in &vertices {
+            writeln!(result, "{} {} {}", v.x, v.y, v.z).unwrap();
+        }
+
+        for [a, b, c] in &faces {
+            writeln!(result, "3 {} {} {}", a, b, c).unwrap();
+        }
+
+        result
+    }
+}
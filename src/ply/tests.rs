@@ -0,0 +1,20 @@
+use crate::parse_stl;
+
+#[test]
+fn test_cube_to_ply_ascii() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    let ply = model.to_ply_ascii();
+    let lines: Vec<&str> = ply.lines().collect();
+
+    assert_eq!(lines[0], "ply");
+    assert_eq!(lines[1], "format ascii 1.0");
+    assert!(lines.contains(&"element vertex 8"));
+    assert!(lines.contains(&"element face 12"));
+
+    let end_header = lines.iter().position(|line| *line == "end_header").unwrap();
+    let body = &lines[end_header + 1..];
+
+    assert_eq!(body.len(), 8 + 12);
+    assert!(body[8..].iter().all(|line| line.starts_with("3 ")));
+}
@@ -0,0 +1,10 @@
+//! Items from the standard prelude that have to be imported explicitly when
+//! building without `std`.
+
+pub use alloc::format;
+pub use alloc::string::{String, ToString};
+pub use alloc::vec;
+pub use alloc::vec::Vec;
+
+#[cfg(not(any(feature = "std", test)))]
+pub use crate::math::FloatExt;
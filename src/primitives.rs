@@ -0,0 +1,161 @@
+use core::f32::consts::{PI, TAU};
+
+use crate::StlModel;
+use crate::geometry::{Triangle, Vec3};
+use crate::prelude::*;
+
+#[cfg(test)]
+mod tests;
+
+/// The fewest segments around a sphere or cylinder that still enclose a
+/// volume.
+const MIN_SEGMENTS: usize = 3;
+
+impl StlModel {
+    /// A cube with sides of length `size`, centred on the origin.
+    ///
+    /// Each face is made of two triangles, for 12 in total. Like every
+    /// primitive, the model is closed and its triangles face outwards with
+    /// matching normals.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::StlModel;
+    ///
+    /// let cube = StlModel::cube(2.0);
+    ///
+    /// assert_eq!(cube.triangles.len(), 12);
+    /// assert_eq!(cube.volume(), 8.0);
+    /// ```
+    pub fn cube(size: f32) -> StlModel {
+        let s = size / 2.0;
+        let corner = |x: f32, y: f32, z: f32| Vec3::new([(x * 2.0 - 1.0) * s, (y * 2.0 - 1.0) * s, (z * 2.0 - 1.0) * s]);
+
+        // The corners of each face, anticlockwise when seen from outside.
+        let faces = [
+            [(0.0, 0.0, 0.0), (0.0, 1.0, 0.0), (1.0, 1.0, 0.0), (1.0, 0.0, 0.0)],
+            [(0.0, 0.0, 1.0), (1.0, 0.0, 1.0), (1.0, 1.0, 1.0), (0.0, 1.0, 1.0)],
+            [(0.0, 0.0, 0.0), (1.0, 0.0, 0.0), (1.0, 0.0, 1.0), (0.0, 0.0, 1.0)],
+            [(0.0, 1.0, 0.0), (0.0, 1.0, 1.0), (1.0, 1.0, 1.0), (1.0, 1.0, 0.0)],
+            [(0.0, 0.0, 0.0), (0.0, 0.0, 1.0), (0.0, 1.0, 1.0), (0.0, 1.0, 0.0)],
+            [(1.0, 0.0, 0.0), (1.0, 1.0, 0.0), (1.0, 1.0, 1.0), (1.0, 0.0, 1.0)]
+        ];
+
+        let mut triangles = Vec::with_capacity(12);
+        for face in faces {
+            let [a, b, c, d] = face.map(|(x, y, z)| corner(x, y, z));
+            triangles.push(triangle(a, b, c));
+            triangles.push(triangle(a, c, d));
+        }
+
+        StlModel::new("cube", triangles)
+    }
+
+    /// A sphere of the given `radius`, centred on the origin.
+    ///
+    /// The sphere is divided into `segments` slices around its axis, like
+    /// the segments of an orange, and `bands = max(segments / 2, 2)` bands
+    /// from pole to pole. At least 3 segments are always used. The triangles
+    /// meet at a point at each pole, so there are
+    /// `2 * segments * (bands - 1)` triangles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::StlModel;
+    ///
+    /// let sphere = StlModel::sphere(1.0, 16);
+    ///
+    /// assert_eq!(sphere.triangles.len(), 2 * 16 * 7);
+    /// assert!(sphere.is_watertight(0.0));
+    /// ```
+    pub fn sphere(radius: f32, segments: usize) -> StlModel {
+        let segments = segments.max(MIN_SEGMENTS);
+        let bands = (segments / 2).max(2);
+
+        let top = Vec3::new([0.0, 0.0, radius]);
+        let bottom = Vec3::new([0.0, 0.0, -radius]);
+
+        // The point where ring `ring`, counted down from the top pole, meets
+        // the edge of slice `slice`. Slices wrap around, so the last edge is
+        // exactly the same point as the first.
+        let point = |ring: usize, slice: usize| {
+            let polar = PI * ring as f32 / bands as f32;
+            let azimuth = TAU * (slice % segments) as f32 / segments as f32;
+            let across = radius * polar.sin();
+
+            Vec3::new([across * azimuth.cos(), across * azimuth.sin(), radius * polar.cos()])
+        };
+
+        let mut triangles = Vec::with_capacity(2 * segments * (bands - 1));
+        for slice in 0..segments {
+            triangles.push(triangle(top, point(1, slice), point(1, slice + 1)));
+
+            for ring in 1..bands - 1 {
+                let (a, b) = (point(ring, slice), point(ring, slice + 1));
+                let (c, d) = (point(ring + 1, slice), point(ring + 1, slice + 1));
+
+                triangles.push(triangle(a, c, d));
+                triangles.push(triangle(a, d, b));
+            }
+
+            triangles.push(triangle(bottom, point(bands - 1, slice + 1), point(bands - 1, slice)));
+        }
+
+        StlModel::new("sphere", triangles)
+    }
+
+    /// A cylinder of the given `radius` and `height`, standing along the z
+    /// axis and centred on the origin.
+    ///
+    /// The round side is made of `segments` flat strips of two triangles
+    /// each, and each end is a fan of `segments` triangles around its
+    /// centre, for `4 * segments` triangles in total. At least 3 segments
+    /// are always used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::StlModel;
+    ///
+    /// let cylinder = StlModel::cylinder(1.0, 2.0, 32);
+    ///
+    /// assert_eq!(cylinder.triangles.len(), 128);
+    /// assert!(cylinder.is_watertight(0.0));
+    /// ```
+    pub fn cylinder(radius: f32, height: f32, segments: usize) -> StlModel {
+        let segments = segments.max(MIN_SEGMENTS);
+        let half = height / 2.0;
+
+        let top = Vec3::new([0.0, 0.0, half]);
+        let bottom = Vec3::new([0.0, 0.0, -half]);
+
+        let point = |slice: usize, z: f32| {
+            let azimuth = TAU * (slice % segments) as f32 / segments as f32;
+
+            Vec3::new([radius * azimuth.cos(), radius * azimuth.sin(), z])
+        };
+
+        let mut triangles = Vec::with_capacity(4 * segments);
+        for slice in 0..segments {
+            let (a, b) = (point(slice, half), point(slice + 1, half));
+            let (c, d) = (point(slice, -half), point(slice + 1, -half));
+
+            triangles.push(triangle(top, a, b));
+            triangles.push(triangle(a, c, d));
+            triangles.push(triangle(a, d, b));
+            triangles.push(triangle(bottom, d, c));
+        }
+
+        StlModel::new("cylinder", triangles)
+    }
+}
+
+/// A triangle with its normal calculated from its vertices.
+fn triangle(a: Vec3, b: Vec3, c: Vec3) -> Triangle {
+    let mut triangle = Triangle { normal: Vec3::new([0.0; 3]), vertices: [a, b, c], attribute: 0 };
+    triangle.normal = triangle.calculate_normal();
+
+    triangle
+}
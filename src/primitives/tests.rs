@@ -0,0 +1,77 @@
+use crate::StlModel;
+use crate::geometry::Vec3;
+
+/// Check the things every primitive should have.
+fn check_closed_and_outward(model: &StlModel) {
+    assert!(model.is_watertight(0.0), "{} is not watertight", model.header);
+    assert!(model.has_consistent_winding(0.0), "{} has inconsistent winding", model.header);
+    assert_eq!(model.normals_point_outward(), Some(true), "{} is inside out", model.header);
+    assert_eq!(model.euler_characteristic(0.0), 2);
+
+    for triangle in &model.triangles {
+        assert!((triangle.normal - triangle.calculate_normal()).length() < 1e-6);
+        assert!((triangle.normal.length() - 1.0).abs() < 1e-5);
+    }
+}
+
+fn assert_close(a: Vec3, b: Vec3) {
+    assert!((a - b).length() < 1e-5, "{:?} is not close to {:?}", a, b);
+}
+
+#[test]
+fn test_cube() {
+    let cube = StlModel::cube(4.0);
+    check_closed_and_outward(&cube);
+
+    assert_eq!(cube.triangles.len(), 12);
+    assert_eq!(cube.volume(), 64.0);
+
+    let bounds = cube.bounding_box().unwrap();
+    assert_eq!(bounds.min, Vec3::new([-2.0; 3]));
+    assert_eq!(bounds.max, Vec3::new([2.0; 3]));
+}
+
+#[test]
+fn test_sphere() {
+    for segments in [3, 4, 7, 16, 64] {
+        let sphere = StlModel::sphere(2.0, segments);
+        check_closed_and_outward(&sphere);
+
+        let bands = (segments / 2).max(2);
+        assert_eq!(sphere.triangles.len(), 2 * segments * (bands - 1));
+
+        for vertex in sphere.triangles.iter().flat_map(|triangle| triangle.vertices) {
+            assert!((vertex.length() - 2.0).abs() < 1e-5);
+        }
+    }
+
+    let bounds = StlModel::sphere(2.0, 64).bounding_box().unwrap();
+    assert_close(bounds.min, Vec3::new([-2.0; 3]));
+    assert_close(bounds.max, Vec3::new([2.0; 3]));
+
+    // The volume gets close to that of a real sphere.
+    let volume = StlModel::sphere(1.0, 128).volume();
+    assert!((volume - 4.0 / 3.0 * core::f32::consts::PI).abs() < 0.01, "{}", volume);
+}
+
+#[test]
+fn test_cylinder() {
+    for segments in [3, 5, 32] {
+        let cylinder = StlModel::cylinder(1.5, 4.0, segments);
+        check_closed_and_outward(&cylinder);
+
+        assert_eq!(cylinder.triangles.len(), 4 * segments);
+    }
+
+    let bounds = StlModel::cylinder(1.5, 4.0, 32).bounding_box().unwrap();
+    assert_close(bounds.min, Vec3::new([-1.5, -1.5, -2.0]));
+    assert_close(bounds.max, Vec3::new([1.5, 1.5, 2.0]));
+}
+
+#[test]
+fn test_too_few_segments_are_raised() {
+    // Three segments and two bands, which is just a cap at each pole.
+    assert_eq!(StlModel::sphere(1.0, 3).triangles.len(), 6);
+    assert_eq!(StlModel::sphere(1.0, 0).triangles.len(), 6);
+    assert_eq!(StlModel::cylinder(1.0, 1.0, 1).triangles.len(), 12);
+}
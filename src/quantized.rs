@@ -0,0 +1,217 @@
+//! A compact, lossy binary format with quantized coordinates.
+//!
+//! This is not a standard STL format and other programs can't read it. It is
+//! only meant for sending models between programs that both use this crate,
+//! written with [`StlModel::as_binary_quantized`] and read with
+//! [`parse_binary_quantized`].
+//!
+//! # Layout
+//!
+//! All numbers are little-endian.
+//!
+//! | Bytes          | Contents                                                |
+//! |----------------|---------------------------------------------------------|
+//! | 6              | The magic bytes `PKSTLQ`                                |
+//! | 1              | The format version, currently 1                         |
+//! | 1              | The number of bits per coordinate, from 1 to 24         |
+//! | 12             | The lowest corner of the bounding box, as three `f32`s  |
+//! | 12             | The highest corner of the bounding box, as three `f32`s |
+//! | 4              | The number of triangles, as a `u32`                     |
+//! | 4              | The length of the model header in bytes, as a `u32`     |
+//! | header length  | The model header, as UTF-8                              |
+//! | the rest       | The packed coordinates                                  |
+//!
+//! Each coordinate is stored as an unsigned integer with the given number of
+//! bits, where 0 is the lowest value of the bounding box along that axis and
+//! the largest integer is the highest. The coordinates are written x, y, then
+//! z for each vertex of each triangle, packed together with no padding, the
+//! lowest bits first. The last byte is padded with zero bits.
+//!
+//! Normals and attributes are not stored. Normals are calculated from the
+//! vertices when reading, and attributes are zero.
+
+use crate::prelude::*;
+
+use crate::StlModel;
+use crate::error::{Error, Result};
+use crate::geometry::{BoundingBox, Triangle, Vec3};
+use crate::io_util::ByteCursor;
+
+#[cfg(test)]
+mod tests;
+
+const MAGIC: &[u8; 6] = b"PKSTLQ";
+const VERSION: u8 = 1;
+const MAX_BITS: u8 = 24;
+
+impl StlModel {
+    /// Convert the model to a compact, lossy binary format.
+    ///
+    /// Each coordinate is rounded to one of `2^bits` evenly spaced values
+    /// across the bounding box of the model, so the error along each axis is
+    /// at most half of the size of the box along that axis divided by
+    /// `2^bits - 1`. `bits` is clamped to between 1 and 24. Normals and
+    /// attributes are not kept.
+    ///
+    /// This is **not** an STL file; see the [`quantized`](crate::quantized)
+    /// module for the layout. Read it back with [`parse_binary_quantized`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    /// use pk_stl::quantized::parse_binary_quantized;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    ///
+    /// let compact = model.as_binary_quantized(8);
+    /// assert!(compact.len() < model.as_binary().len() / 4);
+    ///
+    /// let parsed = parse_binary_quantized(&compact).unwrap();
+    /// assert_eq!(parsed.triangles, model.triangles);
+    /// ```
+    pub fn as_binary_quantized(&self, bits: u8) -> Vec<u8> {
+        let bits = bits.clamp(1, MAX_BITS);
+        let origin = Vec3::new([0.0; 3]);
+        let bounds = self.bounding_box().unwrap_or(BoundingBox { min: origin, max: origin });
+        let header = self.header.as_bytes();
+
+        let mut result = Vec::new();
+        result.extend_from_slice(MAGIC);
+        result.push(VERSION);
+        result.push(bits);
+        for value in bounds.min.as_arr().into_iter().chain(bounds.max.as_arr()) {
+            result.extend_from_slice(&value.to_le_bytes());
+        }
+        result.extend_from_slice(&(self.triangles.len() as u32).to_le_bytes());
+        result.extend_from_slice(&(header.len() as u32).to_le_bytes());
+        result.extend_from_slice(header);
+
+        let steps = ((1u32 << bits) - 1) as f32;
+        let size = bounds.size();
+        let mut writer = BitWriter { bytes: result, buffer: 0, buffered: 0 };
+
+        for triangle in &self.triangles {
+            for vertex in &triangle.vertices {
+                for axis in 0..3 {
+                    let value = if size[axis] > 0.0 {
+                        ((vertex[axis] - bounds.min[axis]) / size[axis] * steps).round()
+                    } else {
+                        0.0
+                    };
+
+                    writer.write(value.clamp(0.0, steps) as u32, bits);
+                }
+            }
+        }
+
+        writer.finish()
+    }
+}
+
+/// Parse a model written by [`StlModel::as_binary_quantized`].
+///
+/// The model has no [source format](StlModel::source_format), since it was
+/// not parsed from an STL file. Errors are reported as binary errors.
+pub fn parse_binary_quantized(bytes: &[u8]) -> Result<StlModel> {
+    let mut reader = ByteCursor::new(bytes);
+
+    if reader.read_bytes(6)? != MAGIC {
+        return Err(Error::binary("Not a quantized model"));
+    }
+
+    let [version] = reader.read_array()?;
+    if version != VERSION {
+        return Err(Error::binary(&format!("Unsupported quantized model version {}", version)));
+    }
+
+    let [bits] = reader.read_array()?;
+    if !(1..=MAX_BITS).contains(&bits) {
+        return Err(Error::binary(&format!("Invalid number of bits per coordinate: {}", bits)));
+    }
+
+    let min = reader.read_f32_triplet()?;
+    let max = reader.read_f32_triplet()?;
+    let triangle_count = reader.read_u32()? as usize;
+    let header_len = reader.read_u32()? as usize;
+    let header = String::from_utf8_lossy(reader.read_bytes(header_len)?).to_string();
+
+    let data = reader.rest();
+    let needed_bits = triangle_count as u64 * 9 * bits as u64;
+    if (data.len() as u64) * 8 < needed_bits {
+        return Err(Error::binary("Unexpected end of data in quantized coordinates"));
+    }
+
+    let steps = ((1u32 << bits) - 1) as f32;
+    let size = max - min;
+    let mut bit_reader = BitReader { bytes: data, position: 0 };
+
+    let mut triangles = Vec::with_capacity(triangle_count);
+    for _ in 0..triangle_count {
+        let mut vertices = [Vec3::new([0.0; 3]); 3];
+
+        for vertex in &mut vertices {
+            for axis in 0..3 {
+                let value = bit_reader.read(bits) as f32;
+                vertex[axis] = min[axis] + value / steps * size[axis];
+            }
+        }
+
+        let mut triangle = Triangle { normal: Vec3::new([0.0; 3]), vertices, attribute: 0 };
+        triangle.normal = triangle.calculate_normal();
+        triangles.push(triangle);
+    }
+
+    Ok(StlModel::new(header, triangles))
+}
+
+/// Writes numbers of any number of bits into bytes, lowest bits first.
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u64,
+    buffered: u32
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, bits: u8) {
+        self.buffer |= (value as u64) << self.buffered;
+        self.buffered += bits as u32;
+
+        while self.buffered >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.buffered -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.buffered > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+
+        self.bytes
+    }
+}
+
+/// Reads numbers written by a [`BitWriter`].
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    /// The index of the next bit to read.
+    position: usize
+}
+
+impl BitReader<'_> {
+    /// Read a number, which must be entirely within the bytes.
+    fn read(&mut self, bits: u8) -> u32 {
+        let mut value = 0;
+
+        for i in 0..bits as usize {
+            let bit = (self.bytes[(self.position + i) / 8] >> ((self.position + i) % 8)) & 1;
+            value |= (bit as u32) << i;
+        }
+
+        self.position += bits as usize;
+
+        value
+    }
+}
@@ -0,0 +1,90 @@
+use super::*;
+use crate::parse_stl;
+
+/// A model with coordinates that don't land on the quantization grid.
+fn irregular_model() -> StlModel {
+    let mut model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    model.subdivide(2);
+
+    for (i, triangle) in model.triangles.iter_mut().enumerate() {
+        for vertex in &mut triangle.vertices {
+            vertex.x = vertex.x * 1.37 - 4.2 + (i % 7) as f32 * 0.013;
+            vertex.y = vertex.y * 0.31 + (i % 5) as f32 * 0.029;
+            vertex.z = vertex.z * 12.5 + 100.0;
+        }
+    }
+
+    model
+}
+
+#[test]
+fn test_quantized_round_trip_within_error_bound() {
+    let model = irregular_model();
+    let size = model.bounding_box().unwrap().size();
+
+    for bits in [4, 8, 12, 16] {
+        let parsed = parse_binary_quantized(&model.as_binary_quantized(bits)).unwrap();
+
+        assert_eq!(parsed.header, model.header);
+        assert_eq!(parsed.triangles.len(), model.triangles.len());
+        assert_eq!(parsed.source_format(), None);
+
+        // Half a step, with a little room for rounding in the arithmetic.
+        let step = size * (1.0 / ((1u32 << bits) - 1) as f32);
+        let tolerance = step * 0.5 + Vec3::new([1e-4, 1e-4, 1e-4]);
+
+        for (original, parsed) in model.triangles.iter().zip(&parsed.triangles) {
+            for (a, b) in original.vertices.iter().zip(&parsed.vertices) {
+                for axis in 0..3 {
+                    assert!((a[axis] - b[axis]).abs() <= tolerance[axis], "{} bits: {:?} became {:?}", bits, a, b);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_quantized_size() {
+    let model = irregular_model();
+    let header_len = 6 + 1 + 1 + 24 + 4 + 4 + model.header.len();
+
+    // 9 coordinates of 10 bits for each triangle, rounded up to whole bytes.
+    let data_bits = model.triangles.len() * 9 * 10;
+    assert_eq!(model.as_binary_quantized(10).len(), header_len + data_bits.div_ceil(8));
+}
+
+#[test]
+fn test_quantized_clamps_bits() {
+    let model = irregular_model();
+
+    assert_eq!(model.as_binary_quantized(0)[7], 1);
+    assert_eq!(model.as_binary_quantized(200)[7], 24);
+}
+
+#[test]
+fn test_quantized_empty_and_flat_models() {
+    let empty = StlModel::new("empty", vec![]);
+    assert_eq!(parse_binary_quantized(&empty.as_binary_quantized(8)).unwrap(), empty);
+
+    let flat = StlModel::new("flat", vec![Triangle::from([
+        [0.0, 0.0, 2.0],
+        [1.0, 0.0, 2.0],
+        [0.0, 1.0, 2.0],
+        [0.0, 0.0, 1.0]
+    ])]);
+    assert_eq!(parse_binary_quantized(&flat.as_binary_quantized(8)).unwrap(), flat);
+}
+
+#[test]
+fn test_parse_invalid_quantized_data() {
+    let bytes = irregular_model().as_binary_quantized(8);
+
+    assert!(parse_binary_quantized(b"").is_err());
+    assert!(parse_binary_quantized(include_bytes!("../../tests/test_cube.stl")).is_err());
+    assert!(parse_binary_quantized(&bytes[..bytes.len() - 1]).is_err());
+    assert!(parse_binary_quantized(&bytes[..20]).is_err());
+
+    let mut wrong_version = bytes.clone();
+    wrong_version[6] = 2;
+    assert!(parse_binary_quantized(&wrong_version).is_err());
+}
@@ -0,0 +1,373 @@
+use crate::prelude::*;
+
+use crate::StlModel;
+use crate::geometry::{BoundingBox, Triangle, Vec3, Vec3f64};
+
+#[cfg(test)]
+mod tests;
+
+impl StlModel {
+    /// Find the first triangle hit by a ray.
+    ///
+    /// The ray starts at `origin` and goes in `direction`, which does not need
+    /// to be unit length. Returns the index of the nearest triangle the ray
+    /// passes through and the point where it hits, or `None` if it misses
+    /// every triangle. Triangles are hit from either side, and triangles
+    /// behind the origin are ignored.
+    ///
+    /// This is useful for picking the triangle under the mouse in a viewer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    ///
+    /// // Look down at the cube from above.
+    /// let (index, point) = model.raycast([5.0, 5.0, 20.0].into(), [0.0, 0.0, -1.0].into()).unwrap();
+    ///
+    /// assert_eq!(point, [5.0, 5.0, 10.0].into());
+    /// assert_eq!(model.triangles[index].normal, [0.0, 0.0, 1.0].into());
+    /// ```
+    pub fn raycast(&self, origin: Vec3, direction: Vec3) -> Option<(usize, Vec3)> {
+        self.triangles.iter()
+            .enumerate()
+            .filter_map(|(i, triangle)| ray_distance(triangle, origin, direction).map(|t| (i, t)))
+            .fold(None, |nearest: Option<(usize, f32)>, (i, t)| match nearest {
+                Some((_, nearest_t)) if nearest_t <= t => nearest,
+                _ => Some((i, t))
+            })
+            .map(|(i, t)| (i, origin + direction * t))
+    }
+
+    /// Find the triangles whose centres are inside a box.
+    ///
+    /// Returns the indices of the triangles whose [centroid] is inside `bbox`
+    /// or on its surface, in order. Triangles that are only partly inside the
+    /// box are included if their centre is. This can be used to select a
+    /// region of a model, for example to crop it with [`StlModel::retain`].
+    ///
+    /// [centroid]: Triangle::centroid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    /// use pk_stl::geometry::BoundingBox;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    ///
+    /// // A box around the top of the cube.
+    /// let bbox = BoundingBox { min: [0.0, 0.0, 9.0].into(), max: [10.0, 10.0, 11.0].into() };
+    ///
+    /// assert_eq!(model.triangles_in_box(&bbox).len(), 2);
+    /// ```
+    pub fn triangles_in_box(&self, bbox: &BoundingBox) -> Vec<usize> {
+        self.triangles.iter()
+            .enumerate()
+            .filter(|(_, triangle)| bbox.contains(triangle.centroid()))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// The distance from a point to the nearest point on the surface of the
+    /// model.
+    ///
+    /// Every triangle is checked, so for many queries on the same model,
+    /// [`TriangleBvh::distance_to_point`] is much faster. Returns infinity if
+    /// the model has no triangles.
+    ///
+    /// [`TriangleBvh::distance_to_point`]: crate::TriangleBvh::distance_to_point
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    ///
+    /// assert_eq!(model.distance_to_point([5.0, 5.0, 13.0].into()), 3.0);
+    /// assert_eq!(model.distance_to_point([5.0, 5.0, 8.0].into()), 2.0);
+    /// ```
+    pub fn distance_to_point(&self, p: Vec3) -> f32 {
+        self.triangles.iter()
+            .map(|triangle| closest_point(triangle, p).distance(p))
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    /// The distance from a point to the surface of the model, negative if
+    /// the point is inside the model.
+    ///
+    /// This is the signed distance function of the model. Whether the point
+    /// is inside is found from the [winding number] of the surface around
+    /// it, which handles small gaps and overlaps in the surface well, and
+    /// does not depend on which way the triangles face. For models that are
+    /// not closed, points in the openings count as inside when they are
+    /// mostly surrounded by the surface.
+    ///
+    /// [winding number]: https://en.wikipedia.org/wiki/Winding_number
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    ///
+    /// assert_eq!(model.signed_distance_to_point([5.0, 5.0, 13.0].into()), 3.0);
+    /// assert_eq!(model.signed_distance_to_point([5.0, 5.0, 8.0].into()), -2.0);
+    /// ```
+    pub fn signed_distance_to_point(&self, p: Vec3) -> f32 {
+        let distance = self.distance_to_point(p);
+
+        if winding_number(&self.triangles, p).abs() > 0.5 {
+            -distance
+        } else {
+            distance
+        }
+    }
+
+    /// Whether a point is inside the model.
+    ///
+    /// This casts a ray from the point and counts how many triangles it
+    /// passes through: an odd number means the point is inside. A ray that
+    /// passes too close to an edge or vertex could be counted twice or not
+    /// at all, so in that case another ray is tried in a slightly different
+    /// direction. If every ray is too close, the winding number is used
+    /// instead, as in [`StlModel::signed_distance_to_point`].
+    ///
+    /// The model should be closed, such as one where
+    /// [`StlModel::is_watertight`] is true, but the way the triangles face
+    /// doesn't matter. Points on the surface may count as inside or outside.
+    /// See [`StlModel::signed_distance_to_point`] for an answer that is more
+    /// forgiving of models with gaps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    ///
+    /// assert!(model.contains_point([5.0, 5.0, 5.0].into()));
+    /// assert!(!model.contains_point([5.0, 5.0, 15.0].into()));
+    /// ```
+    pub fn contains_point(&self, p: Vec3) -> bool {
+        contains_point_along(&self.triangles, p, ray_directions())
+    }
+}
+
+/// The directions [`StlModel::contains_point`] casts rays in, each one
+/// randomly tilted away from positive x.
+fn ray_directions() -> impl Iterator<Item = Vec3f64> {
+    let mut random = 0x2545_f491u32;
+    let mut jitter = move || {
+        random ^= random << 13;
+        random ^= random >> 17;
+        random ^= random << 5;
+
+        random as f64 / u32::MAX as f64 - 0.5
+    };
+
+    core::iter::repeat_with(move || Vec3f64::new([1.0, jitter(), jitter()])).take(MAX_RAYS)
+}
+
+/// Whether a point is inside the triangles, using the first ray that doesn't
+/// graze an edge or vertex.
+///
+/// If every ray grazes something, this falls back to the winding number of
+/// the surface around the point, as [`StlModel::signed_distance_to_point`]
+/// uses.
+fn contains_point_along(triangles: &[Triangle], p: Vec3, directions: impl IntoIterator<Item = Vec3f64>) -> bool {
+    for direction in directions {
+        if let Some(crossings) = ray_crossings(triangles, p.into(), direction) {
+            return crossings % 2 == 1;
+        }
+    }
+
+    winding_number(triangles, p).abs() > 0.5
+}
+
+/// How many rays [`StlModel::contains_point`] tries before giving up on
+/// finding one that doesn't graze an edge or vertex.
+const MAX_RAYS: usize = 8;
+
+/// How close, in barycentric coordinates, a ray can pass to the edge of a
+/// triangle before it is treated as grazing it.
+const GRAZING_TOLERANCE: f64 = 1e-9;
+
+/// Count the triangles a ray passes through, or `None` if it passes too close
+/// to an edge or vertex, or along a triangle, to be sure of the count.
+fn ray_crossings(triangles: &[Triangle], origin: Vec3f64, direction: Vec3f64) -> Option<usize> {
+    let mut crossings = 0;
+
+    for triangle in triangles {
+        let [a, b, c] = triangle.vertices.map(Vec3f64::from);
+        let edge1 = b - a;
+        let edge2 = c - a;
+        let offset = origin - a;
+
+        let p = direction.cross(edge2);
+        let determinant = edge1.dot(p);
+
+        // The ray is parallel to the triangle, which only matters if it runs
+        // along the plane of the triangle.
+        let scale = edge1.length() * edge2.length() * direction.length();
+        if determinant.abs() <= scale * GRAZING_TOLERANCE {
+            let normal = edge1.cross(edge2);
+            let length = normal.length();
+
+            if length > 0.0 && offset.dot(normal).abs() / length <= (offset.length() + 1.0) * GRAZING_TOLERANCE {
+                return None;
+            }
+
+            continue;
+        }
+
+        let inverse = 1.0 / determinant;
+        let u = offset.dot(p) * inverse;
+        let q = offset.cross(edge1);
+        let v = direction.dot(q) * inverse;
+        let t = edge2.dot(q) * inverse;
+
+        let outside = u < -GRAZING_TOLERANCE || v < -GRAZING_TOLERANCE || u + v > 1.0 + GRAZING_TOLERANCE;
+        if t <= 0.0 || outside {
+            continue;
+        }
+
+        if u < GRAZING_TOLERANCE || v < GRAZING_TOLERANCE || u + v > 1.0 - GRAZING_TOLERANCE {
+            return None;
+        }
+
+        crossings += 1;
+    }
+
+    Some(crossings)
+}
+
+/// The point on a triangle nearest to `p`.
+///
+/// This finds which vertex, edge, or the face the nearest point is on using
+/// barycentric coordinates, as described in Real-Time Collision Detection by
+/// Christer Ericson.
+pub(crate) fn closest_point(triangle: &Triangle, p: Vec3) -> Vec3 {
+    let [a, b, c] = triangle.vertices;
+    let ab = b - a;
+    let ac = c - a;
+
+    let ap = p - a;
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+
+    let bp = p - b;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+
+    let cp = p - c;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+
+    // Inside the face. A degenerate triangle has no face, but then one of the
+    // cases above has already been taken.
+    let scale = 1.0 / (va + vb + vc);
+    a + ab * (vb * scale) + ac * (vc * scale)
+}
+
+/// How many times the triangles wrap around a point.
+///
+/// This is the sum of the solid angles of the triangles as seen from the
+/// point, divided by the solid angle of a whole sphere. It is 1 inside a
+/// closed model with outward facing triangles, -1 inside one with inward
+/// facing triangles, and 0 outside.
+pub(crate) fn winding_number(triangles: &[Triangle], p: Vec3) -> f64 {
+    let p = Vec3f64::from(p);
+
+    let total: f64 = triangles.iter()
+        .map(|triangle| {
+            // The solid angle, using the formula of Van Oosterom and
+            // Strackee.
+            let [a, b, c] = triangle.vertices.map(|v| Vec3f64::from(v) - p);
+            let (la, lb, lc) = (a.length(), b.length(), c.length());
+
+            let numerator = a.dot(b.cross(c));
+            let denominator = la * lb * lc + a.dot(b) * lc + b.dot(c) * la + c.dot(a) * lb;
+
+            2.0 * numerator.atan2(denominator)
+        })
+        .sum();
+
+    total / (4.0 * core::f64::consts::PI)
+}
+
+/// Intersect a ray with a triangle using the Möller–Trumbore algorithm.
+///
+/// Returns how many lengths of `direction` along the ray the hit is, if the
+/// ray hits the triangle in front of the origin.
+pub(crate) fn ray_distance(triangle: &Triangle, origin: Vec3, direction: Vec3) -> Option<f32> {
+    // Relative to the sizes of the triangle and direction, so that the
+    // checks work the same whatever units the model is in.
+    const EPSILON: f32 = 1e-7;
+
+    let [a, b, c] = triangle.vertices;
+    let edge1 = b - a;
+    let edge2 = c - a;
+
+    let p = direction.cross(edge2);
+    let determinant = edge1.dot(p);
+
+    // The ray is parallel to the triangle, or the triangle or direction has
+    // no size.
+    let scale = edge1.length() * edge2.length() * direction.length();
+    if determinant.abs() <= scale * EPSILON {
+        return None;
+    }
+
+    let inverse = 1.0 / determinant;
+    let offset = origin - a;
+
+    let u = offset.dot(p) * inverse;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let q = offset.cross(edge1);
+    let v = direction.dot(q) * inverse;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = edge2.dot(q) * inverse;
+
+    // Skip hits at the origin itself, allowing for as much rounding as a
+    // triangle of this size can cause.
+    if t * direction.length() > edge1.length().max(edge2.length()) * EPSILON {
+        Some(t)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,174 @@
+use crate::parse_stl;
+use crate::geometry::{BoundingBox, Vec3, Vec3f64};
+use super::{contains_point_along, ray_crossings, ray_directions};
+
+#[test]
+fn test_raycast_hits_cube_side() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let origin = [-5.0, 2.0, 3.0].into();
+
+    let (index, point) = model.raycast(origin, [1.0, 0.0, 0.0].into()).unwrap();
+
+    // The nearest face is the one at x = 0, five units away, even though the
+    // ray also passes through the face at x = 10.
+    assert_eq!(model.triangles[index].normal, [-1.0, 0.0, 0.0].into());
+    assert_eq!(point, [0.0, 2.0, 3.0].into());
+    assert_eq!((point - origin).length(), 5.0);
+
+    // The length of the direction doesn't matter.
+    assert_eq!(model.raycast(origin, [0.25, 0.0, 0.0].into()), Some((index, point)));
+}
+
+#[test]
+fn test_raycast_from_inside() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    let (index, point) = model.raycast([5.0, 5.0, 5.0].into(), [0.0, 1.0, 0.0].into()).unwrap();
+
+    assert_eq!(model.triangles[index].normal, [0.0, 1.0, 0.0].into());
+    assert_eq!(point, [5.0, 10.0, 5.0].into());
+}
+
+#[test]
+fn test_raycast_small_model() {
+    let mut model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    model.map_vertices(|v| v * 1e-3);
+    let bvh = crate::TriangleBvh::new(&model);
+
+    let origin = [-5e-3, 2e-3, 3e-3].into();
+
+    for direction in [[1.0, 0.0, 0.0], [1e-4, 0.0, 0.0], [1e-4, 1e-5, 0.0]] {
+        let (index, point) = model.raycast(origin, direction.into()).unwrap();
+
+        assert_eq!(model.triangles[index].normal, [-1.0, 0.0, 0.0].into());
+        assert!((point.x).abs() < 1e-9, "{:?}", point);
+        assert_eq!(bvh.raycast(origin, direction.into()), Some((index, point)));
+    }
+}
+
+#[test]
+fn test_raycast_misses() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    // Pointing away from the cube.
+    assert_eq!(model.raycast([-5.0, 2.0, 3.0].into(), [-1.0, 0.0, 0.0].into()), None);
+    // Passing beside it.
+    assert_eq!(model.raycast([-5.0, 20.0, 3.0].into(), [1.0, 0.0, 0.0].into()), None);
+    // No direction at all.
+    assert_eq!(model.raycast([-5.0, 2.0, 3.0].into(), [0.0, 0.0, 0.0].into()), None);
+}
+
+#[test]
+fn test_triangles_in_half_of_cube() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let half = BoundingBox {
+        min: [0.0, 0.0, 0.0].into(),
+        max: [5.0, 10.0, 10.0].into()
+    };
+
+    let inside = model.triangles_in_box(&half);
+
+    // Both triangles of the face at x = 0, and one triangle from each of the
+    // four faces that cross the middle.
+    assert_eq!(inside.len(), 6);
+    for (i, triangle) in model.triangles.iter().enumerate() {
+        assert_eq!(inside.contains(&i), triangle.centroid().x <= 5.0);
+    }
+
+    let everything = model.bounding_box().unwrap();
+    assert_eq!(model.triangles_in_box(&everything), (0..12).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_distance_to_point_outside_cube() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    // Nearest to a face, an edge, and a corner.
+    assert_eq!(model.distance_to_point([5.0, 5.0, 13.0].into()), 3.0);
+    assert_eq!(model.distance_to_point([13.0, 5.0, 14.0].into()), 5.0);
+    assert_eq!(model.distance_to_point([-1.0, -2.0, -2.0].into()), 3.0);
+
+    assert_eq!(model.signed_distance_to_point([13.0, 5.0, 14.0].into()), 5.0);
+    assert_eq!(model.signed_distance_to_point([-1.0, -2.0, -2.0].into()), 3.0);
+}
+
+#[test]
+fn test_signed_distance_inside_cube() {
+    let mut model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    assert_eq!(model.distance_to_point([5.0, 5.0, 5.0].into()), 5.0);
+    assert_eq!(model.signed_distance_to_point([5.0, 5.0, 5.0].into()), -5.0);
+    assert_eq!(model.signed_distance_to_point([1.0, 7.0, 5.0].into()), -1.0);
+    assert_eq!(model.signed_distance_to_point([5.0, 5.0, 10.0].into()), 0.0);
+
+    // Turning the cube inside out doesn't change which side is inside.
+    for triangle in &mut model.triangles {
+        triangle.flip();
+    }
+    assert_eq!(model.signed_distance_to_point([1.0, 7.0, 5.0].into()), -1.0);
+    assert_eq!(model.signed_distance_to_point([5.0, 5.0, 13.0].into()), 3.0);
+}
+
+#[test]
+fn test_distance_to_empty_model() {
+    let model = crate::StlModel::new("empty", vec![]);
+
+    assert_eq!(model.distance_to_point([0.0; 3].into()), f32::INFINITY);
+    assert_eq!(model.signed_distance_to_point([0.0; 3].into()), f32::INFINITY);
+}
+
+#[test]
+fn test_contains_point_in_cube() {
+    let mut model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    for inside in [[5.0, 5.0, 5.0], [0.5, 9.5, 0.5], [9.99, 0.01, 5.0], [5.0, 5.0, 9.0]] {
+        assert!(model.contains_point(inside.into()), "{:?}", inside);
+    }
+
+    for outside in [[5.0, 5.0, 15.0], [-5.0, 5.0, 5.0], [15.0, 5.0, 5.0], [10.01, 5.0, 5.0], [-1.0, -1.0, -1.0]] {
+        assert!(!model.contains_point(outside.into()), "{:?}", outside);
+    }
+
+    // The way the triangles face doesn't matter.
+    for triangle in &mut model.triangles {
+        triangle.flip();
+    }
+    assert!(model.contains_point([5.0, 5.0, 5.0].into()));
+    assert!(!model.contains_point([-5.0, 5.0, 5.0].into()));
+
+    assert!(!crate::StlModel::new("empty", vec![]).contains_point([0.0; 3].into()));
+}
+
+#[test]
+fn test_grazing_rays_are_rejected() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let centre = Vec3f64::new([5.0, 5.0, 5.0]);
+
+    // Straight through a corner, and along an edge of a face.
+    assert_eq!(ray_crossings(&model.triangles, centre, Vec3f64::new([1.0, 1.0, 1.0])), None);
+    assert_eq!(ray_crossings(&model.triangles, Vec3f64::new([-5.0, 0.0, 5.0]), Vec3f64::new([1.0, 0.0, 0.0])), None);
+
+    // Through the middle of a triangle.
+    assert_eq!(ray_crossings(&model.triangles, centre, Vec3f64::new([1.0, 0.1, 0.3])), Some(1));
+    assert_eq!(ray_crossings(&model.triangles, Vec3f64::new([-5.0, 5.1, 5.3]), Vec3f64::new([1.0, 0.0, 0.0])), Some(2));
+}
+
+#[test]
+fn test_contains_point_when_rays_graze() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let centre = Vec3::new([5.0, 5.0, 5.0]);
+    let corner = Vec3f64::new([1.0, 1.0, 1.0]);
+    let edge = Vec3f64::new([1.0, 1.0, 0.0]);
+    let opposite_corner = Vec3f64::new([-1.0, -1.0, -1.0]);
+    let opposite_edge = Vec3f64::new([-1.0, -1.0, 0.0]);
+
+    // The first ray goes straight through a corner, so the next one is used.
+    let directions = core::iter::once(corner).chain(ray_directions());
+    assert!(contains_point_along(&model.triangles, centre, directions));
+
+    // Every ray goes through a corner or along an edge, so the winding number
+    // decides.
+    let grazing = [corner, edge, opposite_corner, opposite_edge];
+    assert!(contains_point_along(&model.triangles, centre, grazing));
+    assert!(!contains_point_along(&model.triangles, Vec3::new([-5.0, 0.0, 5.0]), [Vec3f64::new([1.0, 0.0, 0.0]); 8]));
+}
@@ -0,0 +1,286 @@
+use alloc::collections::{BTreeSet, BinaryHeap};
+use core::cmp::Reverse;
+
+use crate::prelude::*;
+
+use crate::StlModel;
+use crate::geometry::{Triangle, Vec3};
+
+#[cfg(test)]
+mod tests;
+
+impl StlModel {
+    /// Split every triangle into four smaller ones, `levels` times.
+    ///
+    /// Each triangle is split at the midpoints of its edges, so one level
+    /// multiplies the number of triangles by four. The shape of the model does
+    /// not change, but the extra vertices can then be moved, for example to
+    /// smooth or displace the surface. The new triangles keep the winding and
+    /// attribute of the triangle they came from, and their normals are
+    /// calculated from their vertices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let mut model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// model.subdivide(2);
+    ///
+    /// assert_eq!(model.triangles.len(), 12 * 4 * 4);
+    /// ```
+    pub fn subdivide(&mut self, levels: usize) {
+        for _ in 0..levels {
+            let mut triangles = Vec::with_capacity(self.triangles.len() * 4);
+
+            for triangle in &self.triangles {
+                let [a, b, c] = triangle.vertices;
+                let midpoint = |p: Vec3, q: Vec3| (p + q) * 0.5;
+                let (ab, bc, ca) = (midpoint(a, b), midpoint(b, c), midpoint(c, a));
+
+                for vertices in [[a, ab, ca], [ab, b, bc], [ca, bc, c], [ab, bc, ca]] {
+                    let mut part = Triangle { vertices, ..*triangle };
+                    part.normal = part.calculate_normal();
+                    triangles.push(part);
+                }
+            }
+
+            self.triangles = triangles;
+        }
+    }
+
+    /// Reduce the number of triangles by collapsing the shortest edges.
+    ///
+    /// Edges are collapsed one at a time, shortest first, by moving one end of
+    /// the edge onto the other and removing the two triangles that shared it.
+    /// This stops when there are no more than `target_triangles` triangles,
+    /// or when no more edges can be collapsed safely. Vertices within
+    /// `epsilon` of each other are welded together first, as in
+    /// [`StlModel::to_indexed`].
+    ///
+    /// An edge is only collapsed if doing so keeps the surface manifold and
+    /// does not turn any triangle over. Edges whose collapse leaves the
+    /// surrounding triangles facing the same way, such as those in flat
+    /// areas, are all collapsed before any others, so the shape of the model
+    /// is kept for as long as possible. The remaining triangles keep their
+    /// attributes, and their normals are calculated from their vertices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let mut model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// model.subdivide(1);
+    /// model.decimate(12, 0.0);
+    ///
+    /// assert_eq!(model.triangles.len(), 12);
+    /// assert!(model.is_watertight(0.0));
+    /// ```
+    pub fn decimate(&mut self, target_triangles: usize, epsilon: f32) {
+        let (vertices, faces) = self.to_indexed(epsilon);
+        let mut mesh = CollapseMesh::new(&vertices, faces);
+
+        // Collapse edges in flat areas first, since that doesn't change the
+        // shape of the model, and only then the rest.
+        mesh.collapse_until(target_triangles, FLAT_COST);
+        mesh.collapse_until(target_triangles, f32::INFINITY);
+
+        let attributes: Vec<u16> = self.triangles.iter().map(|triangle| triangle.attribute).collect();
+
+        self.triangles = mesh.faces.iter()
+            .zip(attributes)
+            .filter_map(|(face, attribute)| {
+                let mut triangle = Triangle {
+                    normal: Vec3::new([0.0; 3]),
+                    vertices: face.as_ref()?.map(|i| vertices[i as usize]),
+                    attribute
+                };
+                triangle.normal = triangle.calculate_normal();
+                Some(triangle)
+            })
+            .collect();
+    }
+}
+
+/// The largest collapse cost (see `CollapseMesh::collapse_cost`) that is
+/// treated as not changing the shape of the model.
+const FLAT_COST: f32 = 1e-6;
+
+/// An indexed mesh that edges can be collapsed in.
+#[derive(Debug, Clone)]
+struct CollapseMesh<'a> {
+    vertices: &'a [Vec3],
+    /// The faces of the mesh, or `None` for faces that have been removed.
+    faces: Vec<Option<[u32; 3]>>,
+    live_faces: usize,
+    /// The faces around each vertex.
+    vertex_faces: Vec<BTreeSet<usize>>,
+    /// Edges that may be collapsed, shortest first. The length is stored as
+    /// its bits, which sort the same way as the lengths since they are never
+    /// negative. Edges are not removed when they stop existing, so some are
+    /// stale.
+    edges: BinaryHeap<Reverse<(u32, u32, u32)>>
+}
+
+impl<'a> CollapseMesh<'a> {
+    fn new(vertices: &'a [Vec3], faces: Vec<[u32; 3]>) -> CollapseMesh<'a> {
+        let mut mesh = CollapseMesh {
+            vertices,
+            faces: Vec::with_capacity(faces.len()),
+            live_faces: faces.len(),
+            vertex_faces: vec![BTreeSet::new(); vertices.len()],
+            edges: BinaryHeap::new()
+        };
+
+        for (i, face) in faces.into_iter().enumerate() {
+            for v in face {
+                mesh.vertex_faces[v as usize].insert(i);
+            }
+
+            mesh.faces.push(Some(face));
+        }
+
+        mesh
+    }
+
+    /// Collapse the shortest edges that cost no more than `max_cost` until
+    /// there are no more than `target_faces` faces left.
+    fn collapse_until(&mut self, target_faces: usize, max_cost: f32) {
+        self.edges.clear();
+        let faces: Vec<[u32; 3]> = self.faces.iter().flatten().copied().collect();
+        for face in faces {
+            self.push_edges(face);
+        }
+
+        while self.live_faces > target_faces {
+            let Some(Reverse((_, a, b))) = self.edges.pop() else {
+                break;
+            };
+
+            self.try_collapse(a, b, max_cost);
+        }
+    }
+
+    fn push_edges(&mut self, face: [u32; 3]) {
+        for i in 0..3 {
+            let (a, b) = (face[i], face[(i + 1) % 3]);
+            let length = (self.vertices[a as usize] - self.vertices[b as usize]).length();
+
+            self.edges.push(Reverse((length.to_bits(), a.min(b), a.max(b))));
+        }
+    }
+
+    /// The vertices that share a face with `v`.
+    fn neighbours(&self, v: u32) -> BTreeSet<u32> {
+        self.vertex_faces[v as usize].iter()
+            .filter_map(|&f| self.faces[f])
+            .flatten()
+            .filter(|&other| other != v)
+            .collect()
+    }
+
+    /// Collapse the edge between `a` and `b` if it exists, it is safe to,
+    /// and it costs no more than `max_cost`.
+    fn try_collapse(&mut self, a: u32, b: u32, max_cost: f32) {
+        let shared: Vec<usize> = self.vertex_faces[a as usize]
+            .intersection(&self.vertex_faces[b as usize])
+            .copied()
+            .collect();
+
+        if shared.is_empty() {
+            return;
+        }
+
+        // The vertices next to both ends must be exactly the third vertices
+        // of the faces on the edge, or the collapse would pinch the surface.
+        let opposite: BTreeSet<u32> = shared.iter()
+            .filter_map(|&f| self.faces[f])
+            .flatten()
+            .filter(|&v| v != a && v != b)
+            .collect();
+        if self.neighbours(a).intersection(&self.neighbours(b)).ne(opposite.iter()) {
+            return;
+        }
+
+        let cost = |keep, remove| self.collapse_cost(keep, remove, &shared).filter(|&cost| cost <= max_cost);
+        let (keep, remove) = match (cost(a, b), cost(b, a)) {
+            (Some(ab), Some(ba)) if ba < ab => (b, a),
+            (Some(_), _) => (a, b),
+            (None, Some(_)) => (b, a),
+            (None, None) => return
+        };
+
+        for &f in &shared {
+            for v in self.faces[f].take().into_iter().flatten() {
+                self.vertex_faces[v as usize].remove(&f);
+            }
+            self.live_faces -= 1;
+        }
+
+        for f in core::mem::take(&mut self.vertex_faces[remove as usize]) {
+            let Some(face) = &mut self.faces[f] else {
+                continue;
+            };
+
+            for v in face.iter_mut() {
+                if *v == remove {
+                    *v = keep;
+                }
+            }
+
+            let face = *face;
+            self.vertex_faces[keep as usize].insert(f);
+            self.push_edges(face);
+        }
+    }
+
+    /// How much moving `remove` onto `keep` would turn the faces around
+    /// `remove`, or `None` if it would turn a face over, flatten it, or make
+    /// it a copy of another face.
+    fn collapse_cost(&self, keep: u32, remove: u32, shared: &[usize]) -> Option<f32> {
+        let existing: BTreeSet<[u32; 3]> = self.vertex_faces[keep as usize].iter()
+            .filter_map(|&f| self.faces[f])
+            .map(|mut face| {
+                face.sort_unstable();
+                face
+            })
+            .collect();
+
+        let mut cost: f32 = 0.0;
+
+        for &f in &self.vertex_faces[remove as usize] {
+            if shared.contains(&f) {
+                continue;
+            }
+
+            let face = self.faces[f]?;
+            let moved = face.map(|v| if v == remove { keep } else { v });
+
+            let mut sorted = moved;
+            sorted.sort_unstable();
+            if existing.contains(&sorted) {
+                return None;
+            }
+
+            let normal = |face: [u32; 3]| {
+                let [a, b, c] = face.map(|v| self.vertices[v as usize]);
+                (b - a).cross(c - a)
+            };
+            let (before, after) = (normal(face), normal(moved));
+
+            if after.length() == 0.0 {
+                return None;
+            }
+
+            let alignment = before.normalize().dot(after.normalize());
+            if alignment <= 0.0 {
+                return None;
+            }
+
+            cost = cost.max(1.0 - alignment);
+        }
+
+        Some(cost)
+    }
+}
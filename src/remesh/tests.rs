@@ -0,0 +1,79 @@
+use crate::parse_stl;
+
+#[test]
+fn test_subdivide_cube() {
+    let original = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let mut model = original.clone();
+
+    model.subdivide(1);
+
+    assert_eq!(model.triangles.len(), 48);
+    assert_eq!(model.bounding_box(), original.bounding_box());
+    assert_eq!(model.surface_area(), original.surface_area());
+    assert_eq!(model.volume(), original.volume());
+    assert!(model.is_watertight(0.0));
+    assert!(model.has_consistent_winding(0.0));
+
+    // Every new triangle faces the same way as the face it came from.
+    for (i, triangle) in model.triangles.iter().enumerate() {
+        assert_eq!(triangle.normal, original.triangles[i / 4].normal);
+    }
+}
+
+#[test]
+fn test_subdivide_levels() {
+    let mut model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    model.subdivide(0);
+    assert_eq!(model.triangles.len(), 12);
+
+    model.subdivide(3);
+    assert_eq!(model.triangles.len(), 12 * 64);
+
+    // Each face of the cube is now an 8 by 8 grid of squares, all sharing
+    // their vertices.
+    assert_eq!(model.to_indexed(0.0).0.len(), 6 * 8 * 8 + 2);
+    assert_eq!(model.genus(0.0), Some(0));
+}
+
+#[test]
+fn test_decimate_subdivided_cube() {
+    let original = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let mut model = original.clone();
+    model.subdivide(2);
+
+    model.decimate(12, 0.0);
+
+    assert_eq!(model.triangles.len(), 12);
+    assert!(model.is_watertight(0.0));
+    assert!(model.has_consistent_winding(0.0));
+
+    // Only vertices in the middle of flat faces were removed, so the shape
+    // is still the cube.
+    assert_eq!(model.bounding_box(), original.bounding_box());
+    assert_eq!(model.volume(), original.volume());
+}
+
+#[test]
+fn test_decimate_stops_at_target() {
+    let mut model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    model.subdivide(2);
+
+    model.decimate(100, 0.0);
+
+    assert!(model.triangles.len() <= 100);
+    assert!(model.triangles.len() >= 98);
+    assert!(model.is_watertight(0.0));
+    assert_eq!(model.volume(), 1000.0);
+}
+
+#[test]
+fn test_decimate_keeps_tetrahedron() {
+    let mut model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    model.decimate(0, 0.0);
+
+    // A closed surface can't get smaller than a tetrahedron.
+    assert!(model.triangles.len() >= 4);
+    assert!(model.is_watertight(0.0));
+    assert!(model.has_consistent_winding(0.0));
+}
@@ -0,0 +1,150 @@
+use alloc::collections::BTreeSet;
+
+use crate::prelude::*;
+
+use crate::StlModel;
+use crate::index::VertexWelder;
+
+#[cfg(test)]
+mod tests;
+
+impl StlModel {
+    /// Find triangles that duplicate an earlier triangle in the model.
+    ///
+    /// Two triangles are duplicates if they have the same three vertices,
+    /// within `epsilon`, regardless of the order or winding of the vertices.
+    /// Normals and attributes are not compared. The first occurrence of a
+    /// triangle is not included, only the indices of the later copies.
+    pub fn duplicate_triangles(&self, epsilon: f32) -> Vec<usize> {
+        let mut welder = VertexWelder::new(epsilon);
+        let mut seen = BTreeSet::new();
+        let mut duplicates = Vec::new();
+
+        for (i, triangle) in self.triangles.iter().enumerate() {
+            let mut key = triangle.vertices.map(|v| welder.insert(v));
+            key.sort_unstable();
+
+            if !seen.insert(key) {
+                duplicates.push(i);
+            }
+        }
+
+        duplicates
+    }
+
+    /// Remove triangles that duplicate an earlier triangle in the model.
+    ///
+    /// See [`StlModel::duplicate_triangles`] for what counts as a duplicate.
+    /// The order of the remaining triangles is preserved. Returns the number
+    /// of triangles removed.
+    pub fn dedup_triangles(&mut self, epsilon: f32) -> usize {
+        let duplicates = self.duplicate_triangles(epsilon);
+
+        let mut duplicates_iter = duplicates.iter().peekable();
+        let mut index = 0;
+        self.triangles.retain(|_| {
+            let is_duplicate = duplicates_iter.next_if_eq(&&index).is_some();
+            index += 1;
+            !is_duplicate
+        });
+
+        duplicates.len()
+    }
+
+    /// Move vertices within `epsilon` of each other to the same position.
+    ///
+    /// Models exported from some programs have vertices that should be shared
+    /// by several triangles but are very slightly apart, which leaves tiny
+    /// gaps that stop the model being [watertight](StlModel::is_watertight).
+    /// This welds them together as [`StlModel::to_indexed`] does, and moves
+    /// every vertex to the position of the first vertex it was welded to.
+    ///
+    /// Returns the number of distinct vertex positions that were merged into
+    /// another. As with [`StlModel::snap_to_grid`], the stored normals may no
+    /// longer be accurate afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let mut model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// model.triangles[0].vertices[0].x += 1e-5;
+    /// assert!(!model.is_watertight(0.0));
+    ///
+    /// assert_eq!(model.weld_vertices(1e-3), 1);
+    /// assert!(model.is_watertight(0.0));
+    /// ```
+    pub fn weld_vertices(&mut self, epsilon: f32) -> usize {
+        let (distinct, _) = self.to_indexed(0.0);
+        let (vertices, faces) = self.to_indexed(epsilon);
+
+        for (triangle, face) in self.triangles.iter_mut().zip(&faces) {
+            triangle.vertices = face.map(|i| vertices[i as usize]);
+        }
+
+        distinct.len() - vertices.len()
+    }
+
+    /// Round every vertex coordinate to the nearest multiple of `spacing`.
+    ///
+    /// Vertices that are almost, but not exactly, in the same place end up
+    /// exactly equal, which makes welding deterministic and binary files
+    /// compress better. A `spacing` of zero or less leaves the model unchanged.
+    ///
+    /// Moving the vertices can change the direction of the triangles, so the
+    /// stored normals may no longer be accurate. Call
+    /// [`StlModel::recalculate_normals`] afterwards if they need to be.
+    /// Triangles much smaller than the grid may collapse to a line or a point.
+    pub fn snap_to_grid(&mut self, spacing: f32) {
+        if spacing <= 0.0 {
+            return;
+        }
+
+        for triangle in &mut self.triangles {
+            for vertex in &mut triangle.vertices {
+                vertex.x = (vertex.x / spacing).round() * spacing;
+                vertex.y = (vertex.y / spacing).round() * spacing;
+                vertex.z = (vertex.z / spacing).round() * spacing;
+            }
+        }
+    }
+
+    /// Replace the normal of every triangle with the one calculated from its
+    /// vertices.
+    ///
+    /// See [`Triangle::calculate_normal`] for how the normal is calculated.
+    ///
+    /// [`Triangle::calculate_normal`]: crate::geometry::Triangle::calculate_normal
+    pub fn recalculate_normals(&mut self) {
+        for triangle in &mut self.triangles {
+            triangle.normal = triangle.calculate_normal();
+        }
+    }
+
+    /// Scale the normal of every triangle to unit length.
+    ///
+    /// STL files are expected to have unit length normals, and some viewers
+    /// shade triangles wrongly without them. Unlike
+    /// [`StlModel::recalculate_normals`], this keeps the direction of the
+    /// stored normals. Zero length normals have no direction, so they are
+    /// left as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let mut model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// model.triangles[0].normal = [0.0, 0.0, -2.0].into();
+    ///
+    /// model.normalize_normals();
+    ///
+    /// assert_eq!(model.triangles[0].normal, [0.0, 0.0, -1.0].into());
+    /// ```
+    pub fn normalize_normals(&mut self) {
+        for triangle in &mut self.triangles {
+            triangle.normal = triangle.normal.normalize();
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use crate::StlModel;
+use crate::geometry::Triangle;
+
+fn model_with_duplicate() -> StlModel {
+    StlModel::new(String::new(), vec![
+        Triangle::from([
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0]
+        ]),
+        Triangle::from([
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0]
+        ]),
+        // The first triangle again, rotated, reversed, and slightly off.
+        Triangle::from([
+            [0.0, 1.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0001],
+            [0.0, 0.0, -1.0]
+        ])
+    ])
+}
+
+#[test]
+fn test_duplicate_triangles() {
+    let model = model_with_duplicate();
+
+    assert_eq!(model.duplicate_triangles(0.001), vec![2]);
+    assert_eq!(model.duplicate_triangles(0.0), Vec::<usize>::new());
+}
+
+#[test]
+fn test_dedup_triangles() {
+    let mut model = model_with_duplicate();
+    let expected = model.triangles[..2].to_vec();
+
+    assert_eq!(model.dedup_triangles(0.001), 1);
+    assert_eq!(model.triangles, expected);
+    assert_eq!(model.dedup_triangles(0.001), 0);
+}
+
+#[test]
+fn test_snap_to_grid_collapses_near_vertices() {
+    let mut model = StlModel::new(String::new(), vec![
+        Triangle::from([
+            [0.1001, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0]
+        ]),
+        Triangle::from([
+            [0.0999, 0.0002, -0.0003],
+            [1.04, 0.0, 0.0],
+            [0.0, 0.96, 0.0],
+            [0.0, 0.0, 1.0]
+        ])
+    ]);
+
+    model.snap_to_grid(0.1);
+
+    assert_eq!(model.triangles[0].vertices, model.triangles[1].vertices);
+    assert_eq!(model.triangles[0].vertices[0], [0.1, 0.0, 0.0].into());
+    assert_eq!(model.to_indexed(0.0).0.len(), 3);
+}
+
+#[test]
+fn test_recalculate_normals() {
+    let mut model = StlModel::new(String::new(), vec![
+        Triangle::from([
+            [0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0]
+        ])
+    ]);
+
+    model.recalculate_normals();
+
+    assert_eq!(model.triangles[0].normal, [0.0, 0.0, -1.0].into());
+}
+
+#[test]
+fn test_normalize_normals_writes_unit_normals() {
+    let mut model = StlModel::new(String::new(), vec![
+        Triangle::from([
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 2.0]
+        ]),
+        Triangle::from([
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0]
+        ])
+    ]);
+
+    model.normalize_normals();
+
+    assert_eq!(model.triangles[0].normal, [0.0, 0.0, 1.0].into());
+    assert_eq!(model.triangles[1].normal, [0.0, 0.0, 0.0].into());
+
+    for written in [model.as_binary(), model.as_ascii().into_bytes()] {
+        let parsed = crate::parse_stl(&written).unwrap();
+        assert_eq!(parsed.triangles[0].normal, [0.0, 0.0, 1.0].into());
+    }
+}
+
+#[test]
+fn test_weld_vertices_closes_seams() {
+    let original = crate::parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let mut model = original.clone();
+
+    // Nudge every copy of the corner at (10, 10, 10) by a different amount,
+    // as if each face had been exported separately.
+    let mut offset = 0.0;
+    for triangle in &mut model.triangles {
+        for vertex in &mut triangle.vertices {
+            if *vertex == [10.0, 10.0, 10.0].into() {
+                offset += 1e-6;
+                vertex.x += offset;
+                vertex.z -= offset;
+            }
+        }
+    }
+    assert!(!model.is_watertight(0.0));
+    assert!(model.is_watertight(1e-4));
+
+    let distinct = model.to_indexed(0.0).0.len();
+    let merged = model.weld_vertices(1e-4);
+
+    assert_eq!(merged, distinct - 8);
+    assert!(model.is_watertight(0.0));
+    assert_eq!(model.to_indexed(0.0).0.len(), 8);
+
+    // Nothing more to merge the second time.
+    assert_eq!(model.weld_vertices(1e-4), 0);
+}
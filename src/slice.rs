@@ -0,0 +1,170 @@
+use crate::StlModel;
+use crate::geometry::Vec3;
+use crate::prelude::*;
+
+#[cfg(test)]
+mod tests;
+
+impl StlModel {
+    /// Intersect the model with the horizontal plane at height `z`.
+    ///
+    /// Returns a line segment for each triangle that crosses the plane. The
+    /// segments are not joined or ordered, but for a closed model they form
+    /// the outline of the cross-section at that height.
+    ///
+    /// Vertices exactly on the plane are treated as being above it. This means
+    /// an edge lying in the plane is only reported once (by the triangle below
+    /// it), and triangles lying in the plane produce no segments, since their
+    /// outline is already given by the neighbouring triangles. Segments with no
+    /// length are never returned.
+    pub fn slice_z(&self, z: f32) -> Vec<(Vec3, Vec3)> {
+        self.triangles.iter()
+            .filter_map(|triangle| {
+                let distances = triangle.vertices.map(|v| v.z - z);
+                intersect_triangle(triangle.vertices, distances)
+            })
+            .collect()
+    }
+
+    /// Intersect the model with an arbitrary plane.
+    ///
+    /// The plane is given by any `point` on it and its `normal`, which does
+    /// not need to be unit length. This is the general form of
+    /// [`StlModel::slice_z`], and the same rules apply: vertices exactly on
+    /// the plane count as being on the side the normal points to, triangles
+    /// lying in the plane produce no segments, and the segments are not joined
+    /// or ordered.
+    pub fn slice_plane(&self, point: Vec3, normal: Vec3) -> Vec<(Vec3, Vec3)> {
+        self.triangles.iter()
+            .filter_map(|triangle| {
+                let distances = triangle.vertices.map(|v| (v - point).dot(normal));
+                intersect_triangle(triangle.vertices, distances)
+            })
+            .collect()
+    }
+
+    /// The outline of the shadow of the model on a plane.
+    ///
+    /// Every vertex is projected onto the plane through the origin with the
+    /// given `normal`, which does not need to be unit length, and the convex
+    /// hull of the projected points is returned as a closed loop of
+    /// segments, going anticlockwise when looking against the normal. This
+    /// is the footprint of the model when it rests on a plate facing that
+    /// way, which is useful for packing parts together.
+    ///
+    /// Concave parts of the outline are filled in, so the footprint of an L
+    /// shape is a triangle or a quadrilateral rather than an L. Returns no
+    /// segments if the normal is zero or the outline has no area, such as
+    /// for a model with no triangles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// let outline = model.projected_outline([0.0, 0.0, 1.0].into());
+    ///
+    /// // The square under the cube.
+    /// assert_eq!(outline.len(), 4);
+    /// assert!(outline.iter().all(|(a, _)| a.z == 0.0));
+    /// ```
+    pub fn projected_outline(&self, normal: Vec3) -> Vec<(Vec3, Vec3)> {
+        let normal = normal.normalize();
+        if normal.length() == 0.0 {
+            return Vec::new();
+        }
+
+        // Two directions along the plane, so that (u, v, normal) is right
+        // handed.
+        let helper = if normal.x.abs() < 0.9 { Vec3::new([1.0, 0.0, 0.0]) } else { Vec3::new([0.0, 1.0, 0.0]) };
+        let u = helper.cross(normal).normalize();
+        let v = normal.cross(u);
+
+        let mut points: Vec<(f32, f32)> = self.triangles.iter()
+            .flat_map(|triangle| triangle.vertices)
+            .map(|vertex| (vertex.dot(u), vertex.dot(v)))
+            .collect();
+
+        let hull = convex_hull_2d(&mut points);
+        if hull.len() < 3 {
+            return Vec::new();
+        }
+
+        let to_plane = |(x, y): (f32, f32)| u * x + v * y;
+
+        (0..hull.len())
+            .map(|i| (to_plane(hull[i]), to_plane(hull[(i + 1) % hull.len()])))
+            .collect()
+    }
+}
+
+/// The convex hull of points in a plane, in anticlockwise order, using
+/// Andrew's monotone chain algorithm.
+///
+/// Points along the edges of the hull are left out. The points are sorted
+/// in place.
+fn convex_hull_2d(points: &mut [(f32, f32)]) -> Vec<(f32, f32)> {
+    if points.is_empty() {
+        return Vec::new();
+    }
+
+    points.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+
+    let cross = |o: (f32, f32), a: (f32, f32), b: (f32, f32)| {
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    };
+
+    let mut hull: Vec<(f32, f32)> = Vec::with_capacity(points.len() + 1);
+
+    // The lower half from left to right, then the upper half back again,
+    // keeping only left turns.
+    let push = |hull: &mut Vec<(f32, f32)>, point, keep: usize| {
+        while hull.len() >= keep + 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], point) <= 0.0 {
+            hull.pop();
+        }
+        hull.push(point);
+    };
+
+    for &point in points.iter() {
+        push(&mut hull, point, 0);
+    }
+
+    let lower = hull.len() - 1;
+    for &point in points.iter().rev().skip(1) {
+        push(&mut hull, point, lower);
+    }
+
+    // The last point is the first one again.
+    hull.pop();
+    hull
+}
+
+/// Find the segment where a triangle crosses a plane, given the signed
+/// distance of each of its vertices from the plane.
+fn intersect_triangle(vertices: [Vec3; 3], distances: [f32; 3]) -> Option<(Vec3, Vec3)> {
+    let mut points = [Vec3::new([0.0; 3]); 2];
+    let mut count = 0;
+
+    for i in 0..3 {
+        let j = (i + 1) % 3;
+        let (p, q) = (vertices[i], vertices[j]);
+        let (dp, dq) = (distances[i], distances[j]);
+
+        if (dp >= 0.0) == (dq >= 0.0) {
+            continue;
+        }
+
+        let t = dp / (dp - dq);
+        if count < 2 {
+            points[count] = p + (q - p) * t;
+        }
+        count += 1;
+    }
+
+    if count != 2 || points[0] == points[1] {
+        return None;
+    }
+
+    Some((points[0], points[1]))
+}
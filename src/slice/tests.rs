@@ -0,0 +1,141 @@
+use crate::{parse_stl, StlModel};
+use crate::geometry::Triangle;
+
+#[test]
+fn test_slice_cube_at_half_height() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    let segments = model.slice_z(5.0);
+
+    // Each of the four sides is made of two triangles that both cross.
+    assert_eq!(segments.len(), 8);
+
+    let mut total_length = 0.0;
+    for (a, b) in &segments {
+        for p in [a, b] {
+            assert_eq!(p.z, 5.0);
+            let on_x_side = p.x == 0.0 || p.x == 10.0;
+            let on_y_side = p.y == 0.0 || p.y == 10.0;
+            assert!(on_x_side || on_y_side, "{:?} is not on the outline", p);
+        }
+
+        total_length += (*b - *a).length();
+    }
+
+    assert!((total_length - 40.0).abs() < 1e-4);
+}
+
+#[test]
+fn test_slice_outside_model_is_empty() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    assert!(model.slice_z(-1.0).is_empty());
+    assert!(model.slice_z(11.0).is_empty());
+}
+
+#[test]
+fn test_slice_through_vertices_and_faces() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    // The top face lies in the plane, so only the side edges along the top
+    // are reported, each once.
+    let segments = model.slice_z(10.0);
+    let total_length: f32 = segments.iter().map(|(a, b)| (*b - *a).length()).sum();
+    assert!((total_length - 40.0).abs() < 1e-4);
+
+    // A triangle touching the plane at a single vertex has no cross-section.
+    let model = StlModel::new(String::new(), vec![Triangle::from([
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, -1.0],
+        [0.0, 1.0, -1.0],
+        [0.0, 0.0, 1.0]
+    ])]);
+    assert!(model.slice_z(0.0).is_empty());
+}
+
+#[test]
+fn test_slice_plane_matches_slice_z() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    assert_eq!(
+        model.slice_plane([0.0, 0.0, 3.0].into(), [0.0, 0.0, 2.0].into()),
+        model.slice_z(3.0)
+    );
+}
+
+#[test]
+fn test_slice_cube_diagonally() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    // The plane through the centre perpendicular to the main diagonal cuts
+    // the cube in a regular hexagon.
+    let segments = model.slice_plane([5.0, 5.0, 5.0].into(), [1.0, 1.0, 1.0].into());
+
+    // Each of the six hexagon edges crosses one face, and the diagonal
+    // splitting three of those faces, giving nine segments.
+    assert_eq!(segments.len(), 9);
+
+    let mut total_length = 0.0;
+    for (a, b) in &segments {
+        for p in [a, b] {
+            assert!((p.x + p.y + p.z - 15.0).abs() < 1e-4);
+        }
+
+        total_length += (*b - *a).length();
+    }
+
+    let side = 50.0f32.sqrt();
+    assert!((total_length - 6.0 * side).abs() < 1e-3);
+}
+
+#[test]
+fn test_projected_outline_of_cube() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    let outline = model.projected_outline([0.0, 0.0, 1.0].into());
+    assert_eq!(outline.len(), 4);
+
+    // A closed loop around the 10 by 10 footprint.
+    for (i, (a, b)) in outline.iter().enumerate() {
+        assert_eq!(*b, outline[(i + 1) % 4].0);
+        assert!((*b - *a).length() - 10.0 < 1e-5);
+        assert_eq!(a.z, 0.0);
+        assert!([0.0, 10.0].contains(&a.x.abs()) && [0.0, 10.0].contains(&a.y.abs()), "{:?}", a);
+    }
+
+    // Anticlockwise when looking down from above.
+    let area: f32 = outline.iter().map(|(a, b)| a.x * b.y - b.x * a.y).sum::<f32>() / 2.0;
+    assert!((area - 100.0).abs() < 1e-3);
+
+    // Looking along a diagonal of the cube gives a hexagon.
+    assert_eq!(model.projected_outline([1.0, 1.0, 1.0].into()).len(), 6);
+}
+
+#[test]
+fn test_projected_outline_fills_in_concave_parts() {
+    // An L shape made of two triangles in the XY plane.
+    let model = StlModel::new("L", vec![
+        Triangle::from([[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 1.0]]),
+        Triangle::from([[2.0, 0.0, 0.0], [2.0, 1.0, 3.0], [1.0, 1.0, 0.0], [0.0, 0.0, 1.0]])
+    ]);
+
+    let outline = model.projected_outline([0.0, 0.0, 2.0].into());
+    let corners: Vec<_> = outline.iter().map(|(a, _)| (a.x.abs(), a.y.abs())).collect();
+
+    // The inner corner at (1, 1) is inside the hull.
+    assert_eq!(outline.len(), 4);
+    assert!(!corners.contains(&(1.0, 1.0)));
+}
+
+#[test]
+fn test_projected_outline_without_area() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    assert!(model.projected_outline([0.0; 3].into()).is_empty());
+    assert!(StlModel::new("empty", vec![]).projected_outline([0.0, 0.0, 1.0].into()).is_empty());
+
+    let flat = StlModel::new("flat", vec![
+        Triangle::from([[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]])
+    ]);
+    assert!(flat.projected_outline([0.0, 0.0, 1.0].into()).is_empty());
+}
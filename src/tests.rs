@@ -1,30 +1,30 @@
+use crate::error::ErrorKind;
 use crate::geometry::Vec3;
 
 use super::*;
 
 #[test]
 fn test_dimension_range() {
-    let model = StlModel {
-        header: String::new(),
-        triangles: vec![
-            Triangle {
-                normal: Vec3 { x: 0.0, y: 0.0, z: 1.0 },
-                vertices: [
-                    Vec3 { x: 0.0, y: 0.0, z: 5.0 },
-                    Vec3 { x: 1.0, y: 0.0, z: 0.0 },
-                    Vec3 { x: 0.0, y: 1.0, z: 0.0 },
-                ]
-            },
-            Triangle {
-                normal: Vec3 { x: 0.0, y: 0.0, z: 1.0 },
-                vertices: [
-                    Vec3 { x: 0.0, y: 0.0, z: 0.0 },
-                    Vec3 { x: 1.0, y: 0.0, z: -1.0 },
-                    Vec3 { x: 0.0, y: 1.0, z: 0.0 },
-                ]
-            },
-        ]
-    };
+    let model = StlModel::new(String::new(), vec![
+        Triangle {
+            normal: Vec3 { x: 0.0, y: 0.0, z: 1.0 },
+            vertices: [
+                Vec3 { x: 0.0, y: 0.0, z: 5.0 },
+                Vec3 { x: 1.0, y: 0.0, z: 0.0 },
+                Vec3 { x: 0.0, y: 1.0, z: 0.0 },
+            ],
+            attribute: 0
+        },
+        Triangle {
+            normal: Vec3 { x: 0.0, y: 0.0, z: 1.0 },
+            vertices: [
+                Vec3 { x: 0.0, y: 0.0, z: 0.0 },
+                Vec3 { x: 1.0, y: 0.0, z: -1.0 },
+                Vec3 { x: 0.0, y: 1.0, z: 0.0 },
+            ],
+            attribute: 0
+        },
+    ]);
 
     assert_eq!(
         model.dimension_range(),
@@ -34,41 +34,509 @@ fn test_dimension_range() {
 
 #[test]
 fn test_dimension_range_for_empty_model() {
-    let model = StlModel {
-        header: String::new(),
-        triangles: vec![]
-    };
+    let model = StlModel::new(String::new(), vec![]);
 
     assert_eq!(model.dimension_range(), None);
 }
 
 #[test]
 fn test_as_binary() {
-    let model = StlModel {
-        header: String::new(),
-        triangles: vec![
-            Triangle {
-                normal: Vec3 { x: 0.0, y: 0.0, z: 1.0 },
-                vertices: [
-                    Vec3 { x: 0.0, y: 0.0, z: 5.0 },
-                    Vec3 { x: 1.0, y: 0.0, z: 0.0 },
-                    Vec3 { x: 0.0, y: 1.0, z: 0.0 },
-                ]
-            },
-            Triangle {
-                normal: Vec3 { x: 0.0, y: 0.0, z: 1.0 },
-                vertices: [
-                    Vec3 { x: 0.0, y: 0.0, z: 0.0 },
-                    Vec3 { x: 1.0, y: 0.0, z: -1.0 },
-                    Vec3 { x: 0.0, y: 1.0, z: 0.0 },
-                ]
-            },
-        ]
-    };
+    let model = StlModel::new(String::new(), vec![
+        Triangle {
+            normal: Vec3 { x: 0.0, y: 0.0, z: 1.0 },
+            vertices: [
+                Vec3 { x: 0.0, y: 0.0, z: 5.0 },
+                Vec3 { x: 1.0, y: 0.0, z: 0.0 },
+                Vec3 { x: 0.0, y: 1.0, z: 0.0 },
+            ],
+            attribute: 0
+        },
+        Triangle {
+            normal: Vec3 { x: 0.0, y: 0.0, z: 1.0 },
+            vertices: [
+                Vec3 { x: 0.0, y: 0.0, z: 0.0 },
+                Vec3 { x: 1.0, y: 0.0, z: -1.0 },
+                Vec3 { x: 0.0, y: 1.0, z: 0.0 },
+            ],
+            attribute: 0
+        },
+    ]);
 
     let binary = model.as_binary();
 
     let reparsed_model = parse_stl(&binary).unwrap();
 
     assert_eq!(model, reparsed_model);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_header_is_borrowed_when_writing() {
+    let model = StlModel::new("  OpenSCAD Model\n", vec![]);
+    let line = model.header_line();
+
+    // Trimming doesn't need a copy, so the header is written straight from
+    // the model.
+    assert!(matches!(line, Cow::Borrowed(_)));
+    assert_eq!(line, "OpenSCAD Model");
+    assert!(core::ptr::eq(line.as_ptr(), model.header[2..].as_ptr()));
+
+    // Newlines inside the header have to be replaced in a copy.
+    let model = StlModel::new("first\nsecond", vec![]);
+    assert!(matches!(model.header_line(), Cow::Owned(_)));
+    assert_eq!(model.header_line(), "first second");
+    assert!(model.as_ascii().starts_with("solid first second\n"));
+    assert!(model.as_binary().starts_with(b"first second\0"));
+}
+
+#[test]
+fn test_as_binary_truncates_long_header() {
+    let header = "a".repeat(200);
+    let model = StlModel::new(header.clone(), vec![]);
+
+    let binary = model.as_binary();
+
+    assert_eq!(binary.len(), 84);
+    assert_eq!(binary[..80], header.as_bytes()[..80]);
+    assert_eq!(parse_stl(&binary).unwrap().header, header[..80]);
+}
+
+#[test]
+fn test_as_binary_does_not_split_characters_in_header() {
+    // Each `é` is two bytes, so the one starting at byte 79 doesn't fit.
+    let header = "a".repeat(79) + "éé";
+    let model = StlModel::new(header, vec![]);
+
+    let binary = model.as_binary();
+
+    assert_eq!(binary.len(), 84);
+    assert_eq!(binary[79], 0);
+    assert_eq!(parse_stl(&binary).unwrap().header, "a".repeat(79));
+
+    // One byte earlier, the first `é` fits exactly.
+    let model = StlModel::new("a".repeat(78) + "éé", vec![]);
+    assert_eq!(parse_stl(&model.as_binary()).unwrap().header, "a".repeat(78) + "é");
+}
+
+#[test]
+fn test_as_binary_pads_short_header() {
+    let binary = StlModel::new("short", vec![]).as_binary();
+
+    assert_eq!(binary.len(), 84);
+    assert_eq!(&binary[..5], b"short");
+    assert!(binary[5..80].iter().all(|&byte| byte == 0));
+}
+
+#[test]
+fn test_ascii_to_binary_has_zero_attributes() {
+    let mut cube = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    for triangle in &mut cube.triangles {
+        triangle.attribute = 0xbeef;
+    }
+
+    // Going through ASCII loses the attributes.
+    let from_ascii = parse_stl(cube.as_ascii().as_bytes()).unwrap();
+    let binary = from_ascii.as_binary();
+
+    assert_eq!(binary.len(), 84 + 12 * 50);
+    for chunk in binary[84..].chunks_exact(50) {
+        assert_eq!(chunk[48..], [0, 0]);
+    }
+
+    // Binary output keeps them.
+    let binary = cube.as_binary();
+    for chunk in binary[84..].chunks_exact(50) {
+        assert_eq!(chunk[48..], 0xbeefu16.to_le_bytes());
+    }
+}
+
+#[test]
+fn test_color_survives_binary_round_trip() {
+    let mut triangle = Triangle::from([
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 1.0]
+    ]);
+    triangle.set_color((255, 16, 0));
+
+    let model = StlModel::new(String::new(), vec![triangle, Triangle::from([[0.0; 3]; 4])]);
+
+    let reparsed_model = parse_stl(&model.as_binary()).unwrap();
+
+    assert_eq!(reparsed_model.triangles[0].color(), Some((255, 16, 0)));
+    assert_eq!(reparsed_model.triangles[1].color(), None);
+    assert_eq!(model, reparsed_model);
+}
+
+#[test]
+fn test_set_color_quantizes_to_five_bits() {
+    let mut triangle = Triangle::from([[0.0; 3]; 4]);
+
+    triangle.set_color((0x12, 0x80, 0xff));
+
+    assert_eq!(triangle.attribute, 0x8000 | (0x02 << 10) | (0x10 << 5) | 0x1f);
+    assert_eq!(triangle.color(), Some((0x10, 0x84, 0xff)));
+}
+
+#[test]
+fn test_as_ascii_with_custom_header() {
+    let mut model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    model.header = "cube model".to_string();
+
+    let ascii = model.as_ascii_with(|header| header.to_uppercase());
+
+    assert!(ascii.starts_with("solid CUBE MODEL\n"));
+    // Only the header changes.
+    assert_eq!(ascii.replacen("CUBE MODEL", "cube model", 1), model.as_ascii());
+
+    let reparsed = parse_stl(ascii.as_bytes()).unwrap();
+    assert_eq!(reparsed.header, "CUBE MODEL");
+    assert_eq!(reparsed.triangles, model.triangles);
+}
+
+#[test]
+fn test_parse_ascii_from_str() {
+    let cube = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    let ascii = cube.as_ascii();
+
+    let model: StlModel = ascii.parse().unwrap();
+
+    assert_eq!(model.header, "OpenSCAD Model");
+    assert_eq!(model.triangles, cube.triangles);
+}
+
+#[test]
+fn test_parse_invalid_from_str_fails() {
+    assert!("not an stl file".parse::<StlModel>().is_err());
+}
+
+#[test]
+fn test_display_reparses_to_equal_model() {
+    let mut cube = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    cube.header = "cube".to_string();
+
+    let text = cube.to_string();
+
+    assert_eq!(text, cube.as_ascii());
+    assert_eq!(parse_stl(text.as_bytes()).unwrap(), cube);
+}
+
+#[test]
+fn test_with_capacity() {
+    let model = StlModel::with_capacity("generated", 100);
+
+    assert_eq!(model.header, "generated");
+    assert!(model.triangles.is_empty());
+    assert!(model.triangles.capacity() >= 100);
+}
+
+#[test]
+fn test_detect_format() {
+    let binary = include_bytes!("../tests/test_cube.stl");
+    let ascii = parse_stl(binary).unwrap().as_ascii();
+
+    assert_eq!(detect_format(binary), Some(StlFormat::Binary));
+    assert_eq!(detect_format(ascii.as_bytes()), Some(StlFormat::Ascii));
+    assert_eq!(detect_format(&binary[..83]), None);
+    assert_eq!(detect_format(b""), None);
+}
+
+#[test]
+fn test_estimated_triangle_count() {
+    let binary = include_bytes!("../tests/test_cube.stl");
+    assert_eq!(estimated_triangle_count(binary), Some(12));
+
+    // Only the count field is read, so it works without the triangles.
+    assert_eq!(estimated_triangle_count(&binary[..84]), Some(12));
+
+    // Six decimal places, like many exporters write.
+    let cube = parse_stl(binary).unwrap();
+    let mut ascii = format!("solid {}\n", cube.header.trim());
+    for triangle in &cube.triangles {
+        let n = triangle.normal;
+        ascii += &format!("  facet normal {:.6e} {:.6e} {:.6e}\n    outer loop\n", n.x, n.y, n.z);
+        for v in &triangle.vertices {
+            ascii += &format!("      vertex {:.6e} {:.6e} {:.6e}\n", v.x, v.y, v.z);
+        }
+        ascii += "    endloop\n  endfacet\n";
+    }
+    ascii += "endsolid\n";
+
+    assert_eq!(parse_stl(ascii.as_bytes()).unwrap().triangles.len(), 12);
+    let estimate = estimated_triangle_count(ascii.as_bytes()).unwrap();
+    assert!((12..=18).contains(&estimate), "{}", estimate);
+
+    assert_eq!(estimated_triangle_count(b"solid"), None);
+    assert_eq!(estimated_triangle_count(b""), None);
+}
+
+#[test]
+fn test_binary_with_solid_header() {
+    let mut model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    model.header = "solid OpenSCAD Model".to_string();
+    let binary = model.as_binary();
+
+    assert!(binary.starts_with(b"solid "));
+    assert_eq!(detect_format(&binary), Some(StlFormat::Binary));
+
+    let parsed = parse_stl(&binary).unwrap();
+    assert_eq!(parsed.source_format(), Some(StlFormat::Binary));
+    assert_eq!(parsed, model);
+
+    // With a byte missing, the size no longer matches and it is read as ASCII.
+    assert_eq!(detect_format(&binary[..binary.len() - 1]), Some(StlFormat::Ascii));
+}
+
+#[test]
+fn test_parse_short_data_is_an_error() {
+    assert!(parse_stl(b"").is_err());
+    assert!(parse_stl(b"solid").is_err());
+}
+
+#[test]
+fn test_garbage_reports_both_formats() {
+    // Claims far more triangles than there are, and isn't text.
+    let garbage: Vec<u8> = (0..200u32).map(|i| (i * 37 % 251) as u8 | 0x80).collect();
+
+    let err = parse_stl(&garbage).unwrap_err();
+    let message = err.to_string();
+
+    assert_eq!(err.kind(), ErrorKind::Format);
+    assert!(message.contains("Binary STL Parse Error"), "{}", message);
+    assert!(message.contains("ASCII STL Parse Error"), "{}", message);
+
+    // Too short for either format.
+    assert_eq!(parse_stl(b"garbage").unwrap_err().kind(), ErrorKind::Format);
+}
+
+#[test]
+fn test_combined_error_includes_binary_reason() {
+    let mut binary = include_bytes!("../tests/test_cube.stl").to_vec();
+    binary.extend_from_slice(&[0; 10]);
+
+    // With the strict size check, the only problem is the size, and that is
+    // still reported alongside the failed ASCII attempt.
+    let err = parse_stl_with_options(&binary, ParseOptions::strict()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Format);
+    assert!(err.to_string().contains("10 unexpected bytes after the last of its 12 triangles"));
+}
+
+#[test]
+fn test_source_format() {
+    let binary = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    assert_eq!(binary.source_format(), Some(StlFormat::Binary));
+
+    let ascii = parse_stl(binary.as_ascii().as_bytes()).unwrap();
+    assert_eq!(ascii.source_format(), Some(StlFormat::Ascii));
+
+    // The source format doesn't affect equality.
+    let generated = StlModel::new("OpenSCAD Model", binary.triangles.clone());
+    assert_eq!(generated.source_format(), None);
+    assert_eq!(generated, ascii);
+
+    // Editing the model doesn't change where it came from.
+    let mut edited = binary.clone();
+    edited.clear();
+    edited.push_triangle(binary.triangles[0]);
+    assert_eq!(edited.source_format(), Some(StlFormat::Binary));
+}
+
+#[test]
+fn test_retain_upward_facing() {
+    let mut model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    let upward: Vec<Triangle> = model.triangles.iter()
+        .filter(|triangle| triangle.normal.z > 0.0)
+        .copied()
+        .collect();
+
+    model.retain(|triangle| triangle.normal.z > 0.0);
+
+    assert_eq!(model.triangles.len(), 2);
+    assert_eq!(model.triangles, upward);
+}
+
+#[test]
+fn test_triangles_mut_edits_normal() {
+    let mut model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    let original = model.clone();
+
+    model.triangles_mut()[3].normal = Vec3::new([0.0, 1.0, 0.0]);
+
+    assert_eq!(model.triangles[3].normal, Vec3::new([0.0, 1.0, 0.0]));
+    assert_eq!(model.triangles[3].vertices, original.triangles[3].vertices);
+    assert_eq!(model.triangles.len(), original.triangles.len());
+    assert_eq!(model.triangles[4..], original.triangles[4..]);
+}
+
+#[test]
+fn test_push_triangle_returns_sequential_indices() {
+    let cube = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    let mut model = StlModel::new("cube", vec![]);
+
+    for (i, triangle) in cube.triangles.iter().enumerate() {
+        assert_eq!(model.push_triangle(*triangle), i);
+        assert_eq!(model.triangles[i], *triangle);
+    }
+
+    assert_eq!(model.triangles, cube.triangles);
+}
+
+#[test]
+fn test_for_each_triangle_mut_zeroes_normals() {
+    let mut model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    let original = model.clone();
+    let mut visited = 0;
+
+    model.for_each_triangle_mut(|triangle| {
+        triangle.normal = Vec3::new([0.0; 3]);
+        visited += 1;
+    });
+
+    assert_eq!(visited, 12);
+    for (triangle, original) in model.triangles.iter().zip(&original.triangles) {
+        assert_eq!(triangle.normal, Vec3::new([0.0; 3]));
+        assert_eq!(triangle.vertices, original.vertices);
+    }
+}
+
+#[test]
+fn test_clear_and_extend_triangles() {
+    let cube = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    let mut model = cube.clone();
+    let capacity = model.triangles.capacity();
+
+    model.clear();
+    assert!(model.triangles.is_empty());
+    assert_eq!(model.header, cube.header);
+    assert_eq!(model.triangles.capacity(), capacity);
+
+    model.extend_triangles(cube.triangles[..5].iter().copied());
+    assert_eq!(model.triangles.len(), 5);
+
+    model.extend_triangles(cube.triangles[5..].iter().copied());
+    assert_eq!(model, cube);
+
+    model.extend_triangles(std::iter::empty());
+    assert_eq!(model.triangles.len(), 12);
+}
+
+#[test]
+fn test_lenient_files_fail_strict_parsing() {
+    let strict = ParseOptions::strict();
+
+    // A binary file with extra bytes after the last triangle.
+    let mut binary = include_bytes!("../tests/test_cube.stl").to_vec();
+    binary.extend_from_slice(&[0; 10]);
+
+    assert_eq!(parse_stl(&binary).unwrap().triangles.len(), 12);
+    assert!(parse_stl_with_options(&binary, strict).is_err());
+
+    // An ASCII file that stops without `endsolid`.
+    let ascii = b"solid foo
+facet normal 0.0 0.0 1.0
+    outer loop
+        vertex 0.0 0.0 5.0
+        vertex 1.0 0.0 5.0
+        vertex 0.0 1.0 5.0
+    endloop
+endfacet
+";
+
+    assert_eq!(parse_stl(ascii).unwrap().triangles.len(), 1);
+    assert!(parse_stl_with_options(ascii, strict).is_err());
+
+    // Each check can be turned on by itself.
+    let size_only = ParseOptions { require_matching_size: true, ..ParseOptions::default() };
+    assert!(parse_stl_with_options(&binary, size_only).is_err());
+    assert!(parse_stl_with_options(ascii, size_only).is_ok());
+
+    let endsolid_only = ParseOptions { require_endsolid: true, ..ParseOptions::default() };
+    assert!(parse_stl_with_options(&binary, endsolid_only).is_ok());
+    assert!(parse_stl_with_options(ascii, endsolid_only).is_err());
+}
+
+#[test]
+fn test_strict_parsing_accepts_well_formed_files() {
+    let binary = include_bytes!("../tests/test_cube.stl");
+    let model = parse_stl_with_options(binary, ParseOptions::strict()).unwrap();
+
+    let ascii = model.as_ascii() + "endsolid OpenSCAD Model\n";
+    assert_eq!(parse_stl_with_options(ascii.as_bytes(), ParseOptions::strict()).unwrap().triangles, model.triangles);
+}
+
+#[test]
+fn test_attribute_policies() {
+    let mut model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    model.triangles[0].set_color((255, 0, 0));
+    model.triangles[1].set_color((0, 128, 255));
+    model.triangles[2].attribute = 0x0123;
+    let binary = model.as_binary();
+
+    let parse = |attributes| {
+        parse_stl_with_options(&binary, ParseOptions { attributes, ..ParseOptions::default() }).unwrap()
+    };
+
+    let preserved = parse(AttributePolicy::Preserve);
+    assert_eq!(preserved, model);
+    assert_eq!(parse_stl(&binary).unwrap(), preserved);
+
+    let ignored = parse(AttributePolicy::Ignore);
+    assert!(ignored.triangles.iter().all(|triangle| triangle.attribute == 0));
+    assert_eq!(ignored.triangles[0].vertices, model.triangles[0].vertices);
+
+    let colors = parse(AttributePolicy::ValidColorsOnly);
+    assert_eq!(colors.triangles[0].color(), Some((255, 0, 0)));
+    assert_eq!(colors.triangles[1].color(), Some((0, 132, 255)));
+    assert_eq!(colors.triangles[2].attribute, 0);
+    assert!(colors.triangles[3..].iter().all(|triangle| triangle.attribute == 0));
+
+    // ASCII files have no attributes to handle.
+    let ascii = model.as_ascii();
+    let options = ParseOptions { attributes: AttributePolicy::ValidColorsOnly, ..ParseOptions::default() };
+    assert_eq!(parse_stl_with_options(ascii.as_bytes(), options).unwrap().triangles.len(), 12);
+}
+
+#[test]
+fn test_try_from_bytes() {
+    let binary: &[u8] = include_bytes!("../tests/test_cube.stl");
+    let model = StlModel::try_from(binary).unwrap();
+
+    assert_eq!(model, parse_stl(binary).unwrap());
+    assert_eq!(model.source_format(), Some(StlFormat::Binary));
+
+    let ascii = model.as_ascii();
+    let from_ascii = StlModel::try_from(ascii.as_bytes()).unwrap();
+    assert_eq!(from_ascii.source_format(), Some(StlFormat::Ascii));
+    assert_eq!(from_ascii.triangles, model.triangles);
+
+    assert!(StlModel::try_from(&b"solid"[..]).is_err());
+}
+
+#[test]
+fn test_from_triangles() {
+    let cube = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    let model: StlModel = cube.triangles.clone().into();
+
+    assert_eq!(model.header, "");
+    assert_eq!(model.triangles, cube.triangles);
+    assert_eq!(model.source_format(), None);
+}
+
+#[test]
+fn test_parse_headerless() {
+    let binary = include_bytes!("../tests/test_cube.stl");
+    let cube = parse_stl(binary).unwrap();
+
+    let model = parse_stl_headerless(binary).unwrap();
+    assert_eq!(model.header, "");
+    assert_eq!(model.triangles, cube.triangles);
+    assert_eq!(model.source_format(), Some(StlFormat::Binary));
+
+    let model = parse_stl_headerless(cube.as_ascii().as_bytes()).unwrap();
+    assert_eq!(model.header, "");
+    assert_eq!(model.triangles, cube.triangles);
+
+    // It can be combined with the other options.
+    let options = ParseOptions { skip_header: true, ..ParseOptions::strict() };
+    assert_eq!(parse_stl_with_options(binary, options).unwrap().header, "");
+}
@@ -0,0 +1,359 @@
+use alloc::collections::{BTreeMap, VecDeque};
+
+use crate::StlModel;
+use crate::prelude::*;
+
+#[cfg(test)]
+mod tests;
+
+impl StlModel {
+    /// Check whether neighbouring triangles all face the same way.
+    ///
+    /// Two triangles sharing an edge face the same way when they go along
+    /// that edge in opposite directions. Vertices within `epsilon` of each
+    /// other are treated as the same vertex when finding shared edges. Edges
+    /// shared by more than two triangles are ignored, since there is no
+    /// consistent way to orient them.
+    pub fn has_consistent_winding(&self, epsilon: f32) -> bool {
+        let (_, faces) = self.to_indexed(epsilon);
+
+        edge_map(&faces).values().all(|uses| match uses.as_slice() {
+            [first, second] => first.forward != second.forward,
+            _ => true
+        })
+    }
+
+    /// Check whether the model is a closed surface with no holes.
+    ///
+    /// A model is watertight when every edge is shared by exactly two
+    /// triangles. Vertices within `epsilon` of each other are treated as the
+    /// same vertex when finding shared edges. A model with no triangles is not
+    /// watertight.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let mut model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// assert!(model.is_watertight(0.0));
+    ///
+    /// model.triangles.pop();
+    /// assert!(!model.is_watertight(0.0));
+    /// ```
+    pub fn is_watertight(&self, epsilon: f32) -> bool {
+        let (_, faces) = self.to_indexed(epsilon);
+        let edges = edge_map(&faces);
+
+        !edges.is_empty() && edges.values().all(|uses| uses.len() == 2)
+    }
+
+    /// The Euler characteristic of the model's surface.
+    ///
+    /// This is `V - E + F`, the number of vertices minus the number of edges
+    /// plus the number of triangles, after welding together vertices within
+    /// `epsilon` of each other. It is 2 for a closed surface without handles,
+    /// such as a cube or a sphere, and decreases by 2 for each handle (such as
+    /// the hole through a torus).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    ///
+    /// assert_eq!(model.euler_characteristic(0.0), 2);
+    /// ```
+    pub fn euler_characteristic(&self, epsilon: f32) -> i64 {
+        let (vertices, faces) = self.to_indexed(epsilon);
+        let edges = edge_map(&faces);
+
+        vertices.len() as i64 - edges.len() as i64 + faces.len() as i64
+    }
+
+    /// The number of handles in the model's surface.
+    ///
+    /// A cube or sphere has a genus of 0, a torus or a mug has a genus of 1,
+    /// and so on. If the model has several separate parts, this is the total
+    /// for all of them. The genus is calculated from the
+    /// [Euler characteristic](StlModel::euler_characteristic), which only
+    /// describes the shape this way for closed surfaces, so `None` is
+    /// returned if the model is not [watertight](StlModel::is_watertight).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let mut model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// assert_eq!(model.genus(0.0), Some(0));
+    ///
+    /// model.triangles.pop();
+    /// assert_eq!(model.genus(0.0), None);
+    /// ```
+    pub fn genus(&self, epsilon: f32) -> Option<i64> {
+        if !self.is_watertight(epsilon) {
+            return None;
+        }
+
+        // Each closed part contributes 2 - 2g to the Euler characteristic.
+        let parts = self.connected_components(epsilon).len() as i64;
+
+        Some(parts - self.euler_characteristic(epsilon) / 2)
+    }
+
+    /// Flip triangles so that neighbouring triangles all face the same way.
+    ///
+    /// Starting from the first triangle of each connected part of the model,
+    /// this walks across shared edges and flips (see [`Triangle::flip`]) any
+    /// neighbour that faces the opposite way. The first triangle of each part
+    /// is never flipped, so if it faces inwards the whole part ends up facing
+    /// inwards.
+    ///
+    /// See [`StlModel::has_consistent_winding`] for how shared edges are found.
+    ///
+    /// [`Triangle::flip`]: crate::geometry::Triangle::flip
+    pub fn fix_winding(&mut self, epsilon: f32) {
+        let (_, faces) = self.to_indexed(epsilon);
+        let edges = edge_map(&faces);
+
+        // The neighbours of each triangle, and whether the triangle and the
+        // neighbour go along the shared edge in the same direction.
+        let mut neighbours: Vec<Vec<(usize, bool)>> = vec![Vec::new(); faces.len()];
+        for uses in edges.values() {
+            if let [first, second] = uses.as_slice() {
+                let same_direction = first.forward == second.forward;
+                neighbours[first.triangle].push((second.triangle, same_direction));
+                neighbours[second.triangle].push((first.triangle, same_direction));
+            }
+        }
+
+        let mut flipped: Vec<Option<bool>> = vec![None; faces.len()];
+        let mut queue = VecDeque::new();
+
+        for seed in 0..faces.len() {
+            if flipped[seed].is_some() {
+                continue;
+            }
+
+            flipped[seed] = Some(false);
+            queue.push_back(seed);
+
+            while let Some(current) = queue.pop_front() {
+                let current_flipped = flipped[current] == Some(true);
+
+                for &(neighbour, same_direction) in &neighbours[current] {
+                    if flipped[neighbour].is_none() {
+                        flipped[neighbour] = Some(same_direction != current_flipped);
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+        }
+
+        for (triangle, flipped) in self.triangles.iter_mut().zip(flipped) {
+            if flipped == Some(true) {
+                triangle.flip();
+            }
+        }
+    }
+
+    /// Whether the triangles of the model face outwards as a whole.
+    ///
+    /// This uses the sign of the [volume](StlModel::volume): it is positive
+    /// when the triangles face outwards and negative when the model is inside
+    /// out. Returns `None` if the volume is zero, such as for a model with no
+    /// triangles or one that is flat.
+    ///
+    /// The answer is only meaningful for closed models whose triangles all
+    /// face the same way, which [`StlModel::fix_winding`] can ensure. A model
+    /// with mixed winding still gets an answer, based on which way most of
+    /// its volume faces.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    ///
+    /// assert_eq!(model.normals_point_outward(), Some(true));
+    /// ```
+    pub fn normals_point_outward(&self) -> Option<bool> {
+        let volume = self.volume();
+
+        if volume == 0.0 {
+            None
+        } else {
+            Some(volume > 0.0)
+        }
+    }
+
+    /// Flip every triangle if the model is inside out.
+    ///
+    /// If [`StlModel::normals_point_outward`] is `Some(false)`, every triangle
+    /// is [flipped](crate::geometry::Triangle::flip), reversing its winding
+    /// and normal. Otherwise nothing changes. Unlike
+    /// [`StlModel::fix_winding`], this never flips triangles relative to each
+    /// other, so run that first if the winding may be inconsistent.
+    ///
+    /// Returns whether the triangles were flipped.
+    pub fn make_normals_outward(&mut self) -> bool {
+        if self.normals_point_outward() != Some(false) {
+            return false;
+        }
+
+        for triangle in &mut self.triangles {
+            triangle.flip();
+        }
+
+        true
+    }
+
+    /// The neighbouring triangle across each edge of each triangle.
+    ///
+    /// Edge `i` of a triangle goes from vertex `i` to vertex `(i + 1) % 3`.
+    /// The neighbour across it is the index of the one other triangle that
+    /// shares that edge, or `None` if the edge is on the boundary of the
+    /// model, is shared by more than two triangles, or joins a vertex to
+    /// itself. Vertices within `epsilon` of each other are treated as the
+    /// same vertex.
+    ///
+    /// This is the building block behind [`StlModel::fix_winding`] and
+    /// similar walks across the surface of a model.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// let adjacency = model.adjacency(0.0);
+    ///
+    /// assert_eq!(adjacency.len(), 12);
+    /// assert!(adjacency.iter().flatten().all(Option::is_some));
+    /// ```
+    pub fn adjacency(&self, epsilon: f32) -> Vec<[Option<usize>; 3]> {
+        let (_, faces) = self.to_indexed(epsilon);
+        let edges = edge_map(&faces);
+
+        faces.iter().enumerate().map(|(triangle, face)| {
+            core::array::from_fn(|i| {
+                let (a, b) = (face[i], face[(i + 1) % 3]);
+
+                match edges.get(&(a.min(b), a.max(b)))?.as_slice() {
+                    [first, second] if first.triangle == triangle => Some(second.triangle),
+                    [first, second] if second.triangle == triangle => Some(first.triangle),
+                    _ => None
+                }
+            })
+        }).collect()
+    }
+
+    /// Split the model into parts that are not connected to each other.
+    ///
+    /// Triangles are in the same part if they share a vertex, directly or
+    /// through other triangles. Vertices within `epsilon` of each other are
+    /// treated as the same vertex. Each part is returned as its own model with
+    /// a copy of the header and other details, in the order of their first
+    /// triangles, and the triangles keep their relative order.
+    ///
+    /// This is useful for separating the individual objects in a file that
+    /// contains several.
+    pub fn connected_components(&self, epsilon: f32) -> Vec<StlModel> {
+        let (vertices, faces) = self.to_indexed(epsilon);
+
+        let mut sets = DisjointSets::new(vertices.len());
+        for face in &faces {
+            sets.union(face[0] as usize, face[1] as usize);
+            sets.union(face[0] as usize, face[2] as usize);
+        }
+
+        let mut component_of_root = BTreeMap::new();
+        let mut components: Vec<StlModel> = Vec::new();
+
+        for (triangle, face) in self.triangles.iter().zip(&faces) {
+            let root = sets.find(face[0] as usize);
+
+            let component = *component_of_root.entry(root).or_insert_with(|| {
+                components.push(StlModel {
+                    header: self.header.clone(),
+                    triangles: Vec::new(),
+                    ..*self
+                });
+                components.len() - 1
+            });
+
+            components[component].triangles.push(*triangle);
+        }
+
+        components
+    }
+}
+
+/// A union-find structure over the integers `0..n`.
+#[derive(Debug, Clone)]
+struct DisjointSets {
+    parents: Vec<usize>
+}
+
+impl DisjointSets {
+    fn new(n: usize) -> DisjointSets {
+        DisjointSets {
+            parents: (0..n).collect()
+        }
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parents[x] != x {
+            // Point each visited node at its grandparent to keep paths short.
+            self.parents[x] = self.parents[self.parents[x]];
+            x = self.parents[x];
+        }
+
+        x
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+
+        if a != b {
+            self.parents[a.max(b)] = a.min(b);
+        }
+    }
+}
+
+/// How a triangle uses one of the edges of the mesh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct EdgeUse {
+    /// The index of the triangle.
+    pub triangle: usize,
+    /// True if the triangle goes along the edge from the lower vertex index
+    /// to the higher one.
+    pub forward: bool
+}
+
+/// Map each edge of an indexed mesh to the triangles that use it.
+///
+/// Edges are keyed by their two vertex indices, lowest first. Edges between a
+/// vertex and itself, which only occur in degenerate triangles, are skipped.
+pub(crate) fn edge_map(faces: &[[u32; 3]]) -> BTreeMap<(u32, u32), Vec<EdgeUse>> {
+    let mut edges: BTreeMap<(u32, u32), Vec<EdgeUse>> = BTreeMap::new();
+
+    for (triangle, face) in faces.iter().enumerate() {
+        for i in 0..3 {
+            let (a, b) = (face[i], face[(i + 1) % 3]);
+
+            if a == b {
+                continue;
+            }
+
+            edges.entry((a.min(b), a.max(b)))
+                .or_default()
+                .push(EdgeUse { triangle, forward: a < b });
+        }
+    }
+
+    edges
+}
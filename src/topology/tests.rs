@@ -0,0 +1,211 @@
+use crate::{parse_stl, StlModel};
+use crate::geometry::Triangle;
+
+#[test]
+fn test_cube_has_consistent_winding() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    assert!(model.has_consistent_winding(0.0));
+}
+
+#[test]
+fn test_fix_winding_flips_back_single_triangle() {
+    let original = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let mut model = original.clone();
+
+    model.triangles[5].flip();
+    assert!(!model.has_consistent_winding(0.0));
+
+    model.fix_winding(0.0);
+
+    assert!(model.has_consistent_winding(0.0));
+    assert_eq!(model, original);
+}
+
+#[test]
+fn test_fix_winding_keeps_first_triangle() {
+    let original = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let mut model = original.clone();
+
+    // With the first triangle flipped, everything else is flipped to match.
+    model.triangles[0].flip();
+    model.fix_winding(0.0);
+
+    assert!(model.has_consistent_winding(0.0));
+    assert_eq!(model.triangles[0], {
+        let mut first = original.triangles[0];
+        first.flip();
+        first
+    });
+    assert!(model.triangles.iter().zip(&original.triangles).all(|(a, b)| a != b));
+}
+
+#[test]
+fn test_make_normals_outward_fixes_inside_out_cube() {
+    let cube = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let mut model = cube.clone();
+    for triangle in &mut model.triangles {
+        triangle.flip();
+    }
+
+    assert_eq!(model.normals_point_outward(), Some(false));
+    assert!(model.make_normals_outward());
+
+    assert_eq!(model.normals_point_outward(), Some(true));
+    assert_eq!(model.triangles, cube.triangles);
+
+    // Already outward, so nothing changes.
+    assert!(!model.make_normals_outward());
+    assert_eq!(model.triangles, cube.triangles);
+}
+
+#[test]
+fn test_normals_point_outward_without_volume() {
+    let mut empty = StlModel::new("empty", vec![]);
+    assert_eq!(empty.normals_point_outward(), None);
+    assert!(!empty.make_normals_outward());
+
+    let flat = StlModel::new("flat", vec![Triangle::from([
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 1.0]
+    ])]);
+    assert_eq!(flat.normals_point_outward(), None);
+}
+
+#[test]
+fn test_connected_components_of_two_cubes() {
+    let cube = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    let mut far_cube = cube.clone();
+    for triangle in &mut far_cube.triangles {
+        for vertex in &mut triangle.vertices {
+            vertex.x += 100.0;
+        }
+    }
+
+    // Interleave the two cubes to make sure order doesn't matter.
+    let mut model = cube.clone();
+    model.triangles = cube.triangles.iter()
+        .zip(&far_cube.triangles)
+        .flat_map(|(a, b)| [*a, *b])
+        .collect();
+
+    let components = model.connected_components(0.0);
+
+    assert_eq!(components, vec![cube, far_cube]);
+}
+
+#[test]
+fn test_is_watertight() {
+    let mut model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    assert!(model.is_watertight(0.0));
+
+    model.triangles.pop();
+    assert!(!model.is_watertight(0.0));
+
+    model.triangles.clear();
+    assert!(!model.is_watertight(0.0));
+}
+
+/// A torus made of quads around a ring, split into triangles.
+fn torus() -> StlModel {
+    let (rings, sides) = (8, 6);
+
+    let point = |ring: usize, side: usize| {
+        let u = (ring % rings) as f32 / rings as f32 * core::f32::consts::TAU;
+        let v = (side % sides) as f32 / sides as f32 * core::f32::consts::TAU;
+        let radius = 3.0 + v.cos();
+
+        [radius * u.cos(), radius * u.sin(), v.sin()]
+    };
+
+    let mut triangles = Vec::new();
+    for ring in 0..rings {
+        for side in 0..sides {
+            let a = point(ring, side);
+            let b = point(ring + 1, side);
+            let c = point(ring + 1, side + 1);
+            let d = point(ring, side + 1);
+
+            triangles.push(Triangle::from([a, b, c, [0.0; 3]]));
+            triangles.push(Triangle::from([a, c, d, [0.0; 3]]));
+        }
+    }
+
+    StlModel::new("torus", triangles)
+}
+
+#[test]
+fn test_euler_characteristic() {
+    let cube = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    assert_eq!(cube.euler_characteristic(0.0), 2);
+
+    assert_eq!(torus().euler_characteristic(0.0), 0);
+
+    assert_eq!(StlModel::new("empty", vec![]).euler_characteristic(0.0), 0);
+}
+
+#[test]
+fn test_genus() {
+    let cube = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    assert_eq!(cube.genus(0.0), Some(0));
+
+    let torus = torus();
+    assert!(torus.is_watertight(0.0));
+    assert_eq!(torus.genus(0.0), Some(1));
+
+    // Two separate cubes still have no handles.
+    let mut two_cubes = cube.clone();
+    two_cubes.triangles.extend(cube.triangles.iter().map(|triangle| {
+        let mut moved = *triangle;
+        for vertex in &mut moved.vertices {
+            vertex.x += 100.0;
+        }
+        moved
+    }));
+    assert_eq!(two_cubes.euler_characteristic(0.0), 4);
+    assert_eq!(two_cubes.genus(0.0), Some(0));
+
+    let mut open = cube;
+    open.triangles.pop();
+    assert_eq!(open.genus(0.0), None);
+}
+
+#[test]
+fn test_cube_adjacency() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let adjacency = model.adjacency(0.0);
+
+    assert_eq!(adjacency.len(), model.triangles.len());
+
+    for (triangle, neighbours) in adjacency.iter().enumerate() {
+        let vertices = model.triangles[triangle].vertices;
+
+        for (i, neighbour) in neighbours.iter().enumerate() {
+            let neighbour = neighbour.expect("every edge of a cube is shared");
+            assert_ne!(neighbour, triangle);
+
+            // The neighbour has both ends of the shared edge and leads back.
+            let other = model.triangles[neighbour].vertices;
+            assert!(other.contains(&vertices[i]));
+            assert!(other.contains(&vertices[(i + 1) % 3]));
+            assert!(adjacency[neighbour].contains(&Some(triangle)));
+        }
+    }
+}
+
+#[test]
+fn test_adjacency_boundary_edges() {
+    // Two triangles making a square share only the diagonal.
+    let model = StlModel::new("square", vec![
+        Triangle::from([[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 0.0, 1.0]]),
+        Triangle::from([[0.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]])
+    ]);
+
+    assert_eq!(model.adjacency(0.0), vec![
+        [None, None, Some(1)],
+        [Some(0), None, None]
+    ]);
+}
@@ -0,0 +1,72 @@
+use crate::StlModel;
+use crate::geometry::{Axis, Vec3};
+
+#[cfg(test)]
+mod tests;
+
+impl StlModel {
+    /// Mirror the model across the plane through the origin perpendicular to
+    /// `axis`.
+    ///
+    /// The chosen coordinate of every vertex and normal is negated. Mirroring
+    /// turns a model inside out, so the winding of every triangle is also
+    /// reversed to keep the triangles facing outwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    /// use pk_stl::geometry::Axis;
+    ///
+    /// let mut model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// model.mirror(Axis::X);
+    ///
+    /// let bounds = model.bounding_box().unwrap();
+    /// assert_eq!((bounds.min.x, bounds.max.x), (-10.0, 0.0));
+    /// assert_eq!(model.volume(), 1000.0);
+    /// ```
+    pub fn mirror(&mut self, axis: Axis) {
+        let mirror = |v: &mut Vec3| match axis {
+            Axis::X => v.x = -v.x,
+            Axis::Y => v.y = -v.y,
+            Axis::Z => v.z = -v.z
+        };
+
+        for triangle in &mut self.triangles {
+            triangle.vertices.iter_mut().for_each(mirror);
+            mirror(&mut triangle.normal);
+            triangle.vertices.swap(1, 2);
+        }
+    }
+
+    /// Move every vertex of the model to a new position.
+    ///
+    /// `f` is called with each vertex of each triangle and returns where it
+    /// should go. This covers deformations with no method of their own, such
+    /// as twisting, bending, or adding noise. Vertices shared by several
+    /// triangles are passed to `f` once for each triangle, so `f` should give
+    /// the same result for the same position to keep the model closed.
+    ///
+    /// The normals are not changed. Call [`StlModel::recalculate_normals`]
+    /// afterwards if the transform rotates or skews the triangles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let mut model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    ///
+    /// // Double the height of the cube.
+    /// model.map_vertices(|v| [v.x, v.y, v.z * 2.0].into());
+    ///
+    /// assert_eq!(model.bounding_box().unwrap().max.z, 20.0);
+    /// ```
+    pub fn map_vertices(&mut self, f: impl Fn(Vec3) -> Vec3) {
+        for triangle in &mut self.triangles {
+            for vertex in &mut triangle.vertices {
+                *vertex = f(*vertex);
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use crate::parse_stl;
+use crate::geometry::Axis;
+
+#[test]
+fn test_mirror_cube_stays_watertight_and_outward() {
+    let original = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    for axis in [Axis::X, Axis::Y, Axis::Z] {
+        let mut model = original.clone();
+        model.mirror(axis);
+
+        assert!(model.is_watertight(0.0));
+        assert!(model.has_consistent_winding(0.0));
+        assert_eq!(model.volume(), original.volume());
+
+        let bounds = model.bounding_box().unwrap();
+        let center = (bounds.min + bounds.max) * 0.5;
+
+        for triangle in &model.triangles {
+            // The stored normal matches the winding and points away from the
+            // middle of the cube.
+            assert_eq!(triangle.normal, triangle.calculate_normal());
+            assert!(triangle.normal.dot(triangle.vertices[0] - center) > 0.0);
+        }
+    }
+}
+
+#[test]
+fn test_mirror_negates_coordinate() {
+    let original = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let mut model = original.clone();
+
+    model.mirror(Axis::X);
+
+    let bounds = model.bounding_box().unwrap();
+    assert_eq!(bounds.min, [-10.0, 0.0, 0.0].into());
+    assert_eq!(bounds.max, [0.0, 10.0, 10.0].into());
+
+    // Mirroring twice gives back the original model.
+    model.mirror(Axis::X);
+    assert_eq!(model, original);
+}
+
+#[test]
+fn test_map_vertices_shear() {
+    let original = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let mut model = original.clone();
+
+    // Shear x by half of z.
+    model.map_vertices(|v| [v.x + v.z * 0.5, v.y, v.z].into());
+
+    let bounds = model.bounding_box().unwrap();
+    assert_eq!(bounds.min, [0.0, 0.0, 0.0].into());
+    assert_eq!(bounds.max, [15.0, 10.0, 10.0].into());
+
+    // A shear keeps the volume.
+    assert_eq!(model.volume(), original.volume());
+
+    // The normals are left alone until they are recalculated.
+    for (sheared, triangle) in model.triangles.iter().zip(&original.triangles) {
+        assert_eq!(sheared.normal, triangle.normal);
+    }
+    model.recalculate_normals();
+    let slanted = model.triangles.iter().filter(|t| t.normal.x.abs() > 0.0 && t.normal.z.abs() > 0.0).count();
+    assert_eq!(slanted, 4);
+}
@@ -0,0 +1,97 @@
+use crate::StlModel;
+
+#[cfg(test)]
+mod tests;
+
+/// A unit of length for the coordinates of a model.
+///
+/// STL files do not record what unit their coordinates are in, so this is
+/// only metadata kept alongside the model. Models start out in millimetres,
+/// which is what most programs assume; use [`StlModel::set_unit`] when a
+/// file is known to use something else.
+///
+/// This type is only available with the `units` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Unit {
+    /// Millimetres.
+    #[default]
+    Millimeter,
+    /// Centimetres, or 10 millimetres.
+    Centimeter,
+    /// Metres, or 1000 millimetres.
+    Meter,
+    /// Inches, or 25.4 millimetres.
+    Inch,
+    /// Feet, or 12 inches.
+    Foot
+}
+
+impl Unit {
+    /// The length of one of this unit in millimetres.
+    pub fn in_millimeters(self) -> f32 {
+        match self {
+            Unit::Millimeter => 1.0,
+            Unit::Centimeter => 10.0,
+            Unit::Meter => 1000.0,
+            Unit::Inch => 25.4,
+            Unit::Foot => 304.8
+        }
+    }
+}
+
+impl StlModel {
+    /// The unit of the coordinates of the model.
+    ///
+    /// This is [`Unit::Millimeter`] unless it has been changed with
+    /// [`StlModel::set_unit`] or [`StlModel::convert_units`].
+    ///
+    /// This method is only available with the `units` feature.
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    /// Set the unit of the coordinates without changing them.
+    ///
+    /// Use this to record the unit a file was written in. To change the
+    /// coordinates to a different unit, use [`StlModel::convert_units`].
+    ///
+    /// This method is only available with the `units` feature.
+    pub fn set_unit(&mut self, unit: Unit) {
+        self.unit = unit;
+    }
+
+    /// Scale the model from its current unit to another.
+    ///
+    /// Every vertex is scaled so that the model keeps the same physical size
+    /// in the new unit, and the unit of the model is set to `to`. Normals are
+    /// not changed.
+    ///
+    /// This method is only available with the `units` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::{parse_stl, Unit};
+    ///
+    /// let mut model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// model.convert_units(Unit::Centimeter);
+    ///
+    /// assert_eq!(model.unit(), Unit::Centimeter);
+    /// assert_eq!(model.bounding_box().unwrap().size(), [1.0, 1.0, 1.0].into());
+    /// ```
+    pub fn convert_units(&mut self, to: Unit) {
+        if to == self.unit {
+            return;
+        }
+
+        let scale = self.unit.in_millimeters() / to.in_millimeters();
+
+        for triangle in &mut self.triangles {
+            for vertex in &mut triangle.vertices {
+                *vertex = *vertex * scale;
+            }
+        }
+
+        self.unit = to;
+    }
+}
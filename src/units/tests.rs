@@ -0,0 +1,41 @@
+use crate::{parse_stl, StlModel, Unit};
+
+#[test]
+fn test_models_start_in_millimeters() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    assert_eq!(model.unit(), Unit::Millimeter);
+
+    assert_eq!(StlModel::new("empty", vec![]).unit(), Unit::Millimeter);
+}
+
+#[test]
+fn test_convert_cube_to_inches() {
+    let mut model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    model.convert_units(Unit::Inch);
+
+    assert_eq!(model.unit(), Unit::Inch);
+
+    let ((x_min, x_max), (y_min, y_max), (z_min, z_max)) = model.dimension_range().unwrap();
+    assert_eq!((x_min, y_min, z_min), (0.0, 0.0, 0.0));
+    for max in [x_max, y_max, z_max] {
+        assert!((max - 10.0 / 25.4).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn test_set_unit_does_not_scale() {
+    let original = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    let mut model = original.clone();
+
+    model.set_unit(Unit::Inch);
+
+    assert_eq!(model.triangles, original.triangles);
+    assert_ne!(model, original);
+
+    // Converting back and forth keeps the model the same size.
+    model.convert_units(Unit::Foot);
+    model.convert_units(Unit::Inch);
+
+    assert_eq!(model.dimension_range(), original.dimension_range());
+}
@@ -0,0 +1,188 @@
+use crate::StlModel;
+use crate::geometry::{Triangle, Vec3};
+use crate::index::VertexWelder;
+use crate::prelude::*;
+use crate::topology::edge_map;
+
+#[cfg(test)]
+mod tests;
+
+/// The problems found in a model by [`StlModel::validate`].
+///
+/// Each field lists one kind of problem. A model with no problems has every
+/// list empty, which can be checked with [`ValidationReport::is_valid`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    /// Indices of triangles with (almost) no area, such as triangles with two
+    /// identical vertices or with all three vertices on a line.
+    pub degenerate_triangles: Vec<usize>,
+    /// Edges shared by more than two triangles, given by the positions of
+    /// their two end vertices. A solid can only have two faces meeting at
+    /// each edge.
+    pub non_manifold_edges: Vec<(Vec3, Vec3)>,
+    /// Pairs of neighbouring triangles that go along their shared edge in the
+    /// same direction, which means one of them is facing the wrong way.
+    pub inconsistent_winding: Vec<(usize, usize)>,
+    /// Indices of triangles with a NaN or infinite value in a vertex or the
+    /// normal.
+    pub non_finite_triangles: Vec<usize>
+}
+
+impl ValidationReport {
+    /// True if no problems were found.
+    pub fn is_valid(&self) -> bool {
+        self.degenerate_triangles.is_empty()
+            && self.non_manifold_edges.is_empty()
+            && self.inconsistent_winding.is_empty()
+            && self.non_finite_triangles.is_empty()
+    }
+}
+
+impl StlModel {
+    /// Check the model for common problems.
+    ///
+    /// Vertices within `epsilon` of each other are treated as the same vertex
+    /// when finding shared edges, and a triangle is degenerate if any vertex
+    /// is within `epsilon` of the line through the other two.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    ///
+    /// assert!(model.validate(1e-6).is_valid());
+    /// ```
+    pub fn validate(&self, epsilon: f32) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
+        // Only the finite triangles are welded to find shared edges, along
+        // with the index in the model of each one.
+        let mut welder = VertexWelder::new(epsilon);
+        let mut faces = Vec::new();
+        let mut indices = Vec::new();
+
+        for (i, triangle) in self.triangles.iter().enumerate() {
+            if !is_finite(triangle) {
+                report.non_finite_triangles.push(i);
+                continue;
+            }
+
+            if is_degenerate(triangle, epsilon) {
+                report.degenerate_triangles.push(i);
+            }
+
+            faces.push(triangle.vertices.map(|vertex| welder.insert(vertex)));
+            indices.push(i);
+        }
+
+        let vertices = welder.into_vertices();
+
+        for (&(a, b), uses) in &edge_map(&faces) {
+            match uses.as_slice() {
+                [first, second] if first.forward == second.forward => {
+                    report.inconsistent_winding.push((indices[first.triangle], indices[second.triangle]));
+                },
+                [_, _, _, ..] => {
+                    report.non_manifold_edges.push((vertices[a as usize], vertices[b as usize]));
+                },
+                _ => {}
+            }
+        }
+
+        report
+    }
+
+    /// Check whether the coordinates are too large for the detail in the
+    /// model to be stored accurately.
+    ///
+    /// Coordinates are stored as `f32`, which has about 7 significant
+    /// digits, so the further a vertex is from the origin the coarser the
+    /// positions it can take. A model placed at 10,000,000 can only have
+    /// vertices a whole unit apart, and any detail smaller than that is lost
+    /// when the file is written or parsed.
+    ///
+    /// Returns a description of the problem for each check that fails, or
+    /// nothing if the model is fine. This compares the spacing of `f32`
+    /// values at the largest coordinate with the shortest edge in the model,
+    /// and warns if the edge is less than 1000 steps long.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pk_stl::parse_stl;
+    ///
+    /// let mut model = parse_stl(include_bytes!("../tests/test_cube.stl")).unwrap();
+    /// assert!(model.precision_warnings().is_empty());
+    ///
+    /// model.map_vertices(|v| v + [1.0e6, 0.0, 0.0].into());
+    /// assert_eq!(model.precision_warnings().len(), 1);
+    /// ```
+    pub fn precision_warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        let vertices = self.triangles.iter().flat_map(|triangle| triangle.vertices);
+        let largest = vertices
+            .flat_map(|v| v.as_arr())
+            .filter(|value| value.is_finite())
+            .fold(0.0f32, |largest, value| largest.max(value.abs()));
+
+        let shortest_edge = self.shortest_edge().unwrap_or(f32::INFINITY);
+
+        // The gap between `largest` and the next f32 after it.
+        let step = f32::from_bits(largest.to_bits() + 1) - largest;
+
+        if shortest_edge.is_finite() && shortest_edge < step * MIN_STEPS_PER_EDGE {
+            let mut warning = format!(
+                "Coordinates reach {}, where f32 values are {} apart, but the shortest edge is only {} long, \
+                 so detail of that size is stored with only about {} steps",
+                largest, step, shortest_edge, (shortest_edge / step) as u64
+            );
+
+            if let Some(bounds) = self.bounding_box() {
+                let center = bounds.min.lerp(bounds.max, 0.5);
+                let size = bounds.size();
+
+                if center.length() > size.x.max(size.y).max(size.z) {
+                    warning.push_str(&format!(
+                        "; the model is centred at {:?}, so moving it to the origin would keep more detail",
+                        center.as_arr()
+                    ));
+                }
+            }
+
+            warnings.push(warning);
+        }
+
+        warnings
+    }
+}
+
+/// How many steps between `f32` values the shortest edge of a model should
+/// span before [`StlModel::precision_warnings`] warns about it.
+const MIN_STEPS_PER_EDGE: f32 = 1000.0;
+
+fn is_finite(triangle: &Triangle) -> bool {
+    triangle.vertices.iter()
+        .chain(Some(&triangle.normal))
+        .all(|v| v.x.is_finite() && v.y.is_finite() && v.z.is_finite())
+}
+
+/// Check whether the height of the triangle, measured from its longest edge,
+/// is at most epsilon.
+fn is_degenerate(triangle: &Triangle, epsilon: f32) -> bool {
+    let [a, b, c] = triangle.vertices;
+
+    let longest_edge = (b - a).length()
+        .max((c - b).length())
+        .max((a - c).length());
+
+    if longest_edge == 0.0 {
+        return true;
+    }
+
+    let twice_area = (b - a).cross(c - a).length();
+
+    twice_area / longest_edge <= epsilon
+}
@@ -0,0 +1,98 @@
+use crate::parse_stl;
+use crate::geometry::{Triangle, Vec3};
+
+#[test]
+fn test_cube_is_valid() {
+    let model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    let report = model.validate(1e-4);
+
+    assert!(report.is_valid(), "{:?}", report);
+}
+
+#[test]
+fn test_broken_model_reports_each_problem() {
+    let mut model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+
+    // Turn the first triangle inside out.
+    model.triangles[0].vertices.swap(1, 2);
+
+    // A fin hanging off the edge from (0, 0, 0) to (10, 0, 0).
+    model.triangles.push(Triangle::from([
+        [0.0, 0.0, 0.0],
+        [10.0, 0.0, 0.0],
+        [5.0, -5.0, -5.0],
+        [0.0, 0.0, 0.0]
+    ]));
+
+    // Three points on a line.
+    model.triangles.push(Triangle::from([
+        [20.0, 0.0, 0.0],
+        [21.0, 1.0, 1.0],
+        [22.0, 2.0, 2.0],
+        [0.0, 0.0, 0.0]
+    ]));
+
+    model.triangles.push(Triangle::from([
+        [30.0, 0.0, 0.0],
+        [31.0, f32::NAN, 0.0],
+        [30.0, 1.0, 0.0],
+        [0.0, 0.0, 1.0]
+    ]));
+
+    let report = model.validate(1e-4);
+
+    assert!(!report.is_valid());
+    assert_eq!(report.degenerate_triangles, vec![13]);
+    assert_eq!(report.non_finite_triangles, vec![14]);
+
+    assert_eq!(report.non_manifold_edges.len(), 1);
+    let (a, b) = report.non_manifold_edges[0];
+    let mut ends = [a, b];
+    ends.sort_by(|p, q| p.x.total_cmp(&q.x));
+    assert_eq!(ends, [Vec3::new([0.0, 0.0, 0.0]), Vec3::new([10.0, 0.0, 0.0])]);
+
+    // Every edge of the flipped triangle disagrees with its neighbour.
+    assert_eq!(report.inconsistent_winding.len(), 3);
+    assert!(report.inconsistent_winding.iter().all(|&(i, j)| i == 0 || j == 0));
+}
+
+#[test]
+fn test_validate_infinite_vertex() {
+    let mut model = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    model.triangles[3].vertices[1].x = f32::INFINITY;
+
+    let report = model.validate(1e-4);
+
+    assert_eq!(report.non_finite_triangles, vec![3]);
+    assert!(report.degenerate_triangles.is_empty());
+    assert!(report.inconsistent_winding.is_empty());
+    assert!(report.non_manifold_edges.is_empty());
+}
+
+#[test]
+fn test_precision_warnings_for_huge_offsets() {
+    let cube = parse_stl(include_bytes!("../../tests/test_cube.stl")).unwrap();
+    assert!(cube.precision_warnings().is_empty());
+
+    // Small details far from the origin.
+    let mut far = cube.clone();
+    far.map_vertices(|v| v * 0.01 + Vec3::new([1.0e7, 0.0, 0.0]));
+
+    let warnings = far.precision_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("Coordinates reach 10000000"), "{}", warnings[0]);
+    assert!(warnings[0].contains("moving it to the origin"), "{}", warnings[0]);
+
+    // A model that is just very large is not a problem.
+    let mut large = cube.clone();
+    large.map_vertices(|v| v * 1.0e6);
+    assert!(large.precision_warnings().is_empty());
+
+    // Nor is a tiny model near the origin.
+    let mut tiny = cube;
+    tiny.map_vertices(|v| v * 1.0e-6);
+    assert!(tiny.precision_warnings().is_empty());
+
+    assert!(crate::StlModel::new("empty", vec![]).precision_warnings().is_empty());
+}